@@ -1,4 +1,7 @@
+mod segments;
+
 use itertools::Itertools;
+use segments::{DecodeError, Diagnosis, GlyphSet, WireState, Wiring};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
@@ -24,109 +27,73 @@ fn parse(filepath: &str) -> Vec<(Vec<String>, Vec<String>)> {
         .collect()
 }
 
-// Consumes a signal (series of letters contaning digits) and outputs
-// the wiring (number of wires + (each digit represents a segment))
-// e.g. "badf" -> (4, 43)
-fn signal_to_wiring(data: &str) -> (u8, u8) {
-    (
-        data.len() as u8,
-        data.chars()
-            .map(|c| 1 << (c as u8 - 'a' as u8))
-            .reduce(|a, b| a | b)
-            .unwrap_or(0u8),
-    )
+fn decode_numbers(
+    glyphs: &GlyphSet,
+    wiring: &Wiring,
+    digits: &[String],
+) -> Result<u64, DecodeError> {
+    // Each glyph is a digit in the base of the glyph set
+    let base = glyphs.len() as u64;
+    digits.iter().try_fold(0u64, |acc, s| {
+        let digit = wiring.decode(glyphs, s)? as u64;
+        acc.checked_mul(base)
+            .and_then(|acc| acc.checked_add(digit))
+            .ok_or(DecodeError::Overflow)
+    })
 }
 
-fn wiring_to_decode(data: &Vec<String>) -> [u8; 10] {
-    // There are:
-    // * one   digit  with 2 segments: 1
-    // * one   digit  with 3 segments: 7
-    // * one   digit  with 4 segments: 4
-    // * one   digit  with 7 segments: 8
-    // * three digits with 5 segments: 2, 3, 5
-    // * three digits with 6 segments: 0, 6, 9
-    // e.g. [2, 3, 4, 5, 5, 5, 6, 6, 6, 7]
-    let mut result = [0u8; 10];
-    let mut data_wires: Vec<_> = data.into_iter().map(|c| signal_to_wiring(c)).collect();
-    data_wires.sort_by(|(l1, _), (l2, _)| l1.cmp(l2));
-
-    // Digit 1 has only two segments, (it's always the first segment)
-    result[1] = data_wires[0].1;
-
-    // Digit 7 has only three segments, (it's always the second segment)
-    result[7] = data_wires[1].1;
-
-    // Digit 4 has only four segments, (it's always the third segment)
-    result[4] = data_wires[2].1;
-
-    // Digit 8 has all segments, (it's always the last segment)
-    result[8] = data_wires.last().unwrap().1;
-
-    // Digit 3 is the only 5 segment (digit that remains the same with digit 1
-    for index in 3..6 {
-        if data_wires[index].1 & result[1] == result[1] {
-            result[3] = data_wires[index].1;
-            break;
+// Reports, for each entry that is not a clean permutation of the digits, the
+// wires that appear to be faulty and the signals that cannot be decoded
+fn diagnose(data: &[(Vec<String>, Vec<String>)]) {
+    let digits = GlyphSet::seven_segment_digits();
+    let mut faulty = 0;
+
+    for (index, (din, dout)) in data.iter().enumerate() {
+        let signals = [din.as_slice(), dout.as_slice()].concat();
+        let diagnosis = match Diagnosis::diagnose(&digits, &signals) {
+            Ok(d) => d,
+            Err(e) => {
+                println!("Entry #{}: {}", index + 1, e);
+                faulty += 1;
+                continue;
+            }
+        };
+
+        if diagnosis.is_clean() {
+            continue;
         }
-    }
 
-    // Digit 9 is the join between 4 and 3
-    result[9] = result[3] | result[4];
-
-    // Digit 5 is the same as 9 when joined with 9, excepting 3
-    for index in 3..6 {
-        if (data_wires[index].1 | result[9] == result[9]) && (data_wires[index].1 != result[3]) {
-            result[5] = data_wires[index].1;
-            break;
-        }
-    }
-
-    // Digit 2 is the remaining 5 segment digit
-    for index in 3..6 {
-        if (data_wires[index].1 != result[5]) && (data_wires[index].1 != result[3]) {
-            result[2] = data_wires[index].1;
-            break;
-        }
-    }
-
-    // Digit 6: if digit joined with 1 is 8, then this digit is 6
-    for index in 6..9 {
-        if data_wires[index].1 | result[1] == result[8] {
-            result[6] = data_wires[index].1;
-            break;
-        }
-    }
-
-    // Digit 0: is the remaining 6 segment digit
-    for index in 6..9 {
-        if (data_wires[index].1 != result[9]) && (data_wires[index].1 != result[6]) {
-            result[0] = data_wires[index].1;
-            break;
+        faulty += 1;
+        println!(
+            "Entry #{}: {} mismatched segments",
+            index + 1,
+            diagnosis.mismatches
+        );
+        for wire in diagnosis
+            .wires
+            .iter()
+            .filter(|w| w.state != WireState::Healthy)
+        {
+            println!(
+                "  wire '{}' -> segment '{}': {:?} (extra={}, missing={})",
+                wire.wire,
+                (b'a' + wire.segment as u8) as char,
+                wire.state,
+                wire.extra,
+                wire.missing
+            );
         }
-    }
-
-    result
-}
-
-fn decode_digit(map: &[u8; 10], input: &str) -> Option<u8> {
-    let (_, wiring) = signal_to_wiring(input);
-
-    for (i, d) in map.iter().enumerate() {
-        if wiring == *d {
-            return Some(i as u8);
+        for signal in &diagnosis.undecodable {
+            match diagnosis.decode(&digits, signal) {
+                Ok(d) => {
+                    println!("  '{}' is likely {}", signal, digits.label(d))
+                }
+                Err(e) => println!("  {}", e),
+            }
         }
     }
 
-    None
-}
-
-fn decode_numbers(map: &[u8; 10], digits: &Vec<String>) -> u64 {
-    digits
-        .into_iter()
-        .rev()
-        .enumerate()
-        .map(|(i, s)| decode_digit(map, s).unwrap() as u64 * 10u64.pow(i as u32))
-        .sum()
+    println!("Faulty entries: {}/{}", faulty, data.len());
 }
 
 fn main() {
@@ -136,13 +103,19 @@ fn main() {
         .expect("Filepath for input not provided");
     let data = parse(&filepath);
 
+    // Noisy inputs are only diagnosed, as they cannot be decoded reliably
+    if std::env::args().any(|a| a == "--diagnose") {
+        diagnose(&data);
+        return;
+    }
+
     // Problem #1
     println!(
         "Problem #1: {}",
         &data
             .iter()
             .map(|(_, d)| {
-                d.into_iter()
+                d.iter()
                     .map(|c| c.len())
                     .filter(|&c| c == 2 || c == 3 || c == 4 || c == 7)
                     .count()
@@ -151,10 +124,41 @@ fn main() {
     );
 
     // Problem #2
+    let digits = GlyphSet::seven_segment_digits();
     println!(
         "Problem #2: {}",
         data.into_iter()
-            .map(|(din, dout)| decode_numbers(&wiring_to_decode(&din), &dout))
-            .sum::<u64>()
+            .map(|(din, dout)| {
+                Wiring::solve(&digits, &din)
+                    .and_then(|wiring| decode_numbers(&digits, &wiring, &dout))
+            })
+            .sum::<Result<u64, _>>()
+            .expect("Unable to decode display")
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_numbers() {
+        let digits = GlyphSet::seven_segment_digits();
+        let wiring = Wiring::from_map((0..7).collect());
+        let eight = String::from("abcdefg");
+
+        assert_eq!(
+            decode_numbers(&digits, &wiring, std::slice::from_ref(&eight)),
+            Ok(8)
+        );
+        // 19 digits fit in 64 bits, 20 eights do not
+        assert_eq!(
+            decode_numbers(&digits, &wiring, &vec![eight.clone(); 19]),
+            Ok(8_888_888_888_888_888_888)
+        );
+        assert_eq!(
+            decode_numbers(&digits, &wiring, &vec![eight; 20]),
+            Err(DecodeError::Overflow)
+        );
+    }
+}
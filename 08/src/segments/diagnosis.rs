@@ -0,0 +1,230 @@
+use super::{signal_to_mask, DecodeError, GlyphSet, Wiring};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireState {
+    Healthy,
+    // Lit in every observed pattern, even when the glyph expects it off
+    StuckOn,
+    // Never lit in any observed pattern, even when the glyph expects it on
+    StuckOff,
+    // Disagrees with the glyphs sometimes, in both directions
+    Faulty,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WireReport {
+    pub wire: char,
+    pub segment: usize,
+    // Number of patterns where the wire was lit but the glyph expects it off
+    pub extra: usize,
+    // Number of patterns where the wire was off but the glyph expects it lit
+    pub missing: usize,
+    pub state: WireState,
+}
+
+// Best effort explanation of a set of noisy signals: instead of requiring
+// every pattern to be a valid glyph, it picks the wiring that minimizes the
+// number of segments that disagree with the closest glyph of each pattern
+#[derive(Debug, Clone)]
+pub struct Diagnosis {
+    pub wiring: Wiring,
+    pub mismatches: usize,
+    pub wires: Vec<WireReport>,
+    // Signals that do not translate exactly into a glyph
+    pub undecodable: Vec<String>,
+}
+
+impl Diagnosis {
+    pub fn diagnose<S: AsRef<str>>(
+        glyphs: &GlyphSet,
+        signals: &[S],
+    ) -> Result<Self, DecodeError> {
+        let wires = glyphs.segments();
+        let patterns = signals
+            .iter()
+            .map(|s| signal_to_mask(s.as_ref(), wires))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut search = Search {
+            glyphs,
+            patterns: &patterns,
+            best: None,
+        };
+        search.assign_wire(&mut Vec::with_capacity(wires), 0);
+        let (mismatches, map) = search.best.ok_or(DecodeError::Inconsistent)?;
+        let wiring = Wiring::from_map(map);
+
+        // Blame each mismatch on the wire connected to the wrong segment
+        let mut extra = vec![0; wires];
+        let mut missing = vec![0; wires];
+        let mut lit = vec![0; wires];
+        let mut undecodable = Vec::new();
+
+        for (signal, pattern) in signals.iter().zip(patterns.iter()) {
+            let translated = wiring.translate(*pattern);
+            let (_, glyph) = closest(glyphs, translated, u32::MAX);
+
+            if glyph != translated {
+                undecodable.push(signal.as_ref().to_owned());
+            }
+
+            for (wire, segment) in wiring.map().iter().enumerate() {
+                let on = pattern & (1 << wire) != 0;
+                let expected = glyph & (1 << segment) != 0;
+
+                lit[wire] += on as usize;
+                extra[wire] += (on && !expected) as usize;
+                missing[wire] += (!on && expected) as usize;
+            }
+        }
+
+        let wires = (0..wires)
+            .map(|wire| WireReport {
+                wire: (b'a' + wire as u8) as char,
+                segment: wiring.map()[wire],
+                extra: extra[wire],
+                missing: missing[wire],
+                state: match (extra[wire], missing[wire], lit[wire]) {
+                    (0, 0, _) => WireState::Healthy,
+                    (_, 0, l) if l == patterns.len() => WireState::StuckOn,
+                    (0, _, 0) => WireState::StuckOff,
+                    _ => WireState::Faulty,
+                },
+            })
+            .collect();
+
+        Ok(Self {
+            wiring,
+            mismatches,
+            wires,
+            undecodable,
+        })
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.mismatches == 0
+    }
+
+    // Decodes a signal into the closest glyph, ignoring the segments driven by
+    // wires that were not found healthy. Fails if the signal is equally close
+    // to more than one glyph
+    pub fn decode(
+        &self,
+        glyphs: &GlyphSet,
+        signal: &str,
+    ) -> Result<usize, DecodeError> {
+        let reliable = self
+            .wires
+            .iter()
+            .filter(|w| w.state == WireState::Healthy)
+            .fold(0u32, |acc, w| acc | (1 << w.segment));
+
+        let translated = self
+            .wiring
+            .translate(signal_to_mask(signal, self.wires.len())?);
+
+        let distances = (0..glyphs.len())
+            .map(|g| ((glyphs.mask(g) ^ translated) & reliable).count_ones())
+            .collect::<Vec<_>>();
+        let best = *distances.iter().min().unwrap();
+
+        match distances.iter().filter(|&&d| d == best).count() {
+            1 => Ok(distances.iter().position(|&d| d == best).unwrap()),
+            _ => Err(DecodeError::UnknownPattern(signal.to_owned())),
+        }
+    }
+}
+
+// Outputs the distance to, and the mask of, the glyph closest to `mask` when
+// only looking at the segments in `known`
+fn closest(glyphs: &GlyphSet, mask: u32, known: u32) -> (usize, u32) {
+    (0..glyphs.len())
+        .map(|g| {
+            let glyph = glyphs.mask(g);
+            (((glyph ^ mask) & known).count_ones() as usize, glyph)
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .unwrap_or((0, 0))
+}
+
+// Branch and bound over all the wirings. The cost of a partial wiring only
+// counts the mismatches on the wires already assigned, which never decreases
+// as more wires are assigned, so it is a valid lower bound
+struct Search<'a> {
+    glyphs: &'a GlyphSet,
+    patterns: &'a [u32],
+    best: Option<(usize, Vec<usize>)>,
+}
+
+impl Search<'_> {
+    fn assign_wire(&mut self, map: &mut Vec<usize>, taken: u32) {
+        let cost = self.cost(map, taken);
+        if matches!(&self.best, Some((best, _)) if cost >= *best) {
+            return;
+        }
+
+        if map.len() == self.glyphs.segments() {
+            self.best = Some((cost, map.clone()));
+            return;
+        }
+
+        for segment in 0..self.glyphs.segments() {
+            if taken & (1 << segment) == 0 {
+                map.push(segment);
+                self.assign_wire(map, taken | (1 << segment));
+                map.pop();
+            }
+        }
+    }
+
+    fn cost(&self, map: &[usize], taken: u32) -> usize {
+        let partial = Wiring::from_map(map.to_vec());
+
+        self.patterns
+            .iter()
+            .map(|p| closest(self.glyphs, partial.translate(*p), taken).0)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnose_stuck_wire() {
+        let digits = GlyphSet::seven_segment_digits();
+
+        // Same display as the puzzle example, with wire 'd' (top segment)
+        // stuck on
+        let signals = [
+            "acedgfb", "cdfbe", "gcdfa", "fbcad", "dab", "cefabd", "cdfgeb",
+            "eafbd", "cagedb", "abd",
+        ];
+        let diagnosis = Diagnosis::diagnose(&digits, &signals).unwrap();
+
+        assert_eq!(diagnosis.mismatches, 1);
+        assert_eq!(diagnosis.undecodable, vec!["eafbd"]);
+        assert!(diagnosis.wires.iter().all(|w| match w.wire {
+            'd' => w.state == WireState::StuckOn,
+            _ => w.state == WireState::Healthy,
+        }));
+        assert_eq!(diagnosis.decode(&digits, "eafbd"), Ok(4));
+    }
+
+    #[test]
+    fn test_diagnose_clean() {
+        let digits = GlyphSet::seven_segment_digits();
+        let signals = [
+            "acedgfb", "cdfbe", "gcdfa", "fbcad", "dab", "cefabd", "cdfgeb",
+            "eafb", "cagedb", "ab",
+        ];
+        let diagnosis = Diagnosis::diagnose(&digits, &signals).unwrap();
+
+        assert!(diagnosis.is_clean());
+        assert_eq!(
+            Some(diagnosis.wiring),
+            Wiring::solve(&digits, &signals).ok()
+        );
+    }
+}
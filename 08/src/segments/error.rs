@@ -0,0 +1,47 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    // Signal contains a character that does not name a wire of the display
+    InvalidWire(char),
+    // Glyph table has more segments than can be named/encoded
+    TooManySegments(usize),
+    // Two glyphs of the same table light up the same segments
+    DuplicateGlyph(char, char),
+    // No wiring maps every observed pattern onto a glyph
+    Inconsistent,
+    // More than one wiring explains the observed patterns
+    Ambiguous,
+    // Signal does not translate into any glyph of the table
+    UnknownPattern(String),
+    // Decoded number does not fit in 64 bits
+    Overflow,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidWire(c) => write!(f, "invalid wire '{}'", c),
+            Self::TooManySegments(n) => {
+                write!(f, "glyph table has too many segments ({})", n)
+            }
+            Self::DuplicateGlyph(a, b) => {
+                write!(f, "glyphs '{}' and '{}' share the same segments", a, b)
+            }
+            Self::Inconsistent => {
+                write!(f, "no wiring is consistent with the observed patterns")
+            }
+            Self::Ambiguous => {
+                write!(f, "several wirings match the observed patterns")
+            }
+            Self::UnknownPattern(s) => {
+                write!(f, "pattern '{}' does not decode into any glyph", s)
+            }
+            Self::Overflow => {
+                write!(f, "decoded number does not fit in 64 bits")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
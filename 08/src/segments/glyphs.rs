@@ -0,0 +1,121 @@
+use super::{signal_to_mask, DecodeError};
+
+// Table of the glyphs a display is able to show. Each glyph is identified by
+// a label and stored as the bitmask of the segments it lights up, where
+// segment 'a' is bit 0, 'b' is bit 1, and so on
+#[derive(Debug, Clone)]
+pub struct GlyphSet {
+    segments: usize,
+    glyphs: Vec<(char, u32)>,
+}
+
+impl GlyphSet {
+    // Segments are named after lowercase letters, so 26 is the limit
+    pub const MAX_SEGMENTS: usize = 26;
+
+    pub fn new(
+        segments: usize,
+        glyphs: &[(char, &str)],
+    ) -> Result<Self, DecodeError> {
+        if segments > Self::MAX_SEGMENTS {
+            return Err(DecodeError::TooManySegments(segments));
+        }
+
+        let mut result = Self {
+            segments,
+            glyphs: Vec::with_capacity(glyphs.len()),
+        };
+
+        for (label, signal) in glyphs {
+            let mask = signal_to_mask(signal, segments)?;
+
+            if let Some(index) = result.find(mask) {
+                return Err(DecodeError::DuplicateGlyph(
+                    result.label(index),
+                    *label,
+                ));
+            }
+
+            result.glyphs.push((*label, mask));
+        }
+
+        Ok(result)
+    }
+
+    //  aaaa
+    // b    c
+    // b    c
+    //  dddd
+    // e    f
+    // e    f
+    //  gggg
+    const DIGITS: [(char, &'static str); 10] = [
+        ('0', "abcefg"),
+        ('1', "cf"),
+        ('2', "acdeg"),
+        ('3', "acdfg"),
+        ('4', "bcdf"),
+        ('5', "abdfg"),
+        ('6', "abdefg"),
+        ('7', "acf"),
+        ('8', "abcdefg"),
+        ('9', "abcdfg"),
+    ];
+
+    pub fn seven_segment_digits() -> Self {
+        Self::new(7, &Self::DIGITS).unwrap()
+    }
+
+    // Same layout as the decimal digits, extended with "AbCdEF"
+    #[allow(dead_code)]
+    pub fn seven_segment_hex() -> Self {
+        let letters = [
+            ('A', "abcdef"),
+            ('B', "bdefg"),
+            ('C', "abeg"),
+            ('D', "cdefg"),
+            ('E', "abdeg"),
+            ('F', "abde"),
+        ];
+
+        Self::new(7, &[&Self::DIGITS[..], &letters].concat()).unwrap()
+    }
+
+    pub fn segments(&self) -> usize {
+        self.segments
+    }
+
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    pub fn label(&self, index: usize) -> char {
+        self.glyphs[index].0
+    }
+
+    pub fn mask(&self, index: usize) -> u32 {
+        self.glyphs[index].1
+    }
+
+    pub fn find(&self, mask: u32) -> Option<usize> {
+        self.glyphs.iter().position(|(_, m)| *m == mask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glyph_sets() {
+        let hex = GlyphSet::seven_segment_hex();
+        assert_eq!(hex.len(), 16);
+        assert_eq!(hex.label(11), 'B');
+        assert_eq!(hex.find(signal_to_mask("bdefg", 7).unwrap()), Some(11));
+
+        assert!(matches!(
+            GlyphSet::new(7, &[('1', "cf"), ('7', "acf"), ('l', "fc")]),
+            Err(DecodeError::DuplicateGlyph('1', 'l'))
+        ));
+    }
+}
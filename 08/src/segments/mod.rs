@@ -0,0 +1,25 @@
+mod diagnosis;
+mod error;
+mod glyphs;
+mod wiring;
+
+pub use diagnosis::{Diagnosis, WireState};
+pub use error::DecodeError;
+pub use glyphs::GlyphSet;
+pub use wiring::Wiring;
+
+// Consumes a signal (series of letters, one per lit wire) and outputs the
+// bitmask of the lit wires. Letters outside the first `wires` letters of the
+// alphabet are rejected
+// e.g. "badf" -> 43
+pub fn signal_to_mask(signal: &str, wires: usize) -> Result<u32, DecodeError> {
+    signal.chars().try_fold(0u32, |acc, c| {
+        let wire = (c as u32).wrapping_sub('a' as u32) as usize;
+
+        if c.is_ascii_lowercase() && wire < wires {
+            Ok(acc | (1 << wire))
+        } else {
+            Err(DecodeError::InvalidWire(c))
+        }
+    })
+}
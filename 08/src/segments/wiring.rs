@@ -0,0 +1,248 @@
+use super::{signal_to_mask, DecodeError, GlyphSet};
+
+// Permutation between the wires of a display and the segments they light up
+// (i.e. `map[wire] == segment`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wiring {
+    map: Vec<usize>,
+}
+
+impl Wiring {
+    // Deduces the wiring of a display from a set of observed signals. Each
+    // signal has to be one of the glyphs in `glyphs`, with its wires
+    // scrambled by the (unknown) wiring
+    //
+    // The search assigns a glyph to each observed pattern, one at a time, and
+    // keeps for every wire the set of segments it can still be connected to:
+    // * wires lit in the pattern must light a segment of the glyph
+    // * wires not lit in the pattern must light a segment outside the glyph
+    // Once all patterns have a glyph, every permutation that fits the
+    // remaining candidates is a valid wiring
+    pub fn solve<S: AsRef<str>>(
+        glyphs: &GlyphSet,
+        signals: &[S],
+    ) -> Result<Self, DecodeError> {
+        let wires = glyphs.segments();
+
+        let mut patterns = signals
+            .iter()
+            .map(|s| signal_to_mask(s.as_ref(), wires))
+            .collect::<Result<Vec<_>, _>>()?;
+        patterns.sort_unstable();
+        patterns.dedup();
+
+        // Patterns with fewer glyph options are resolved first, as they prune
+        // the search the most (e.g. the digit 1 in a seven segment display)
+        patterns.sort_by_key(|p| {
+            (0..glyphs.len())
+                .filter(|&g| glyphs.mask(g).count_ones() == p.count_ones())
+                .count()
+        });
+
+        let mut search = Search {
+            glyphs,
+            patterns: &patterns,
+            used: vec![false; glyphs.len()],
+            solutions: Vec::new(),
+        };
+        search.assign_glyph(0, vec![Self::all_segments(wires); wires]);
+
+        match search.solutions.len() {
+            0 => Err(DecodeError::Inconsistent),
+            1 => Ok(Self {
+                map: search.solutions.pop().unwrap(),
+            }),
+            _ => Err(DecodeError::Ambiguous),
+        }
+    }
+
+    pub fn from_map(map: Vec<usize>) -> Self {
+        Self { map }
+    }
+
+    pub fn map(&self) -> &[usize] {
+        &self.map
+    }
+
+    // Converts a bitmask of wires into the bitmask of lit segments
+    pub fn translate(&self, mask: u32) -> u32 {
+        self.map
+            .iter()
+            .enumerate()
+            .filter(|(wire, _)| mask & (1 << wire) != 0)
+            .fold(0, |acc, (_, segment)| acc | (1 << segment))
+    }
+
+    // Outputs the index of the glyph represented by the signal
+    pub fn decode(
+        &self,
+        glyphs: &GlyphSet,
+        signal: &str,
+    ) -> Result<usize, DecodeError> {
+        glyphs
+            .find(self.translate(signal_to_mask(signal, self.map.len())?))
+            .ok_or_else(|| DecodeError::UnknownPattern(signal.to_owned()))
+    }
+
+    fn all_segments(segments: usize) -> u32 {
+        ((1u64 << segments) - 1) as u32
+    }
+}
+
+struct Search<'a> {
+    glyphs: &'a GlyphSet,
+    patterns: &'a [u32],
+    used: Vec<bool>,
+    solutions: Vec<Vec<usize>>,
+}
+
+impl Search<'_> {
+    // Two solutions are enough to know the wiring is ambiguous
+    const MAX_SOLUTIONS: usize = 2;
+
+    fn assign_glyph(&mut self, index: usize, candidates: Vec<u32>) {
+        if index == self.patterns.len() {
+            let mut map = Vec::with_capacity(candidates.len());
+            self.assign_wire(&candidates, 0, &mut map);
+            return;
+        }
+
+        let pattern = self.patterns[index];
+        let all = Wiring::all_segments(candidates.len());
+
+        for glyph in 0..self.glyphs.len() {
+            if self.solutions.len() >= Self::MAX_SOLUTIONS {
+                return;
+            }
+
+            let mask = self.glyphs.mask(glyph);
+            if self.used[glyph] || mask.count_ones() != pattern.count_ones() {
+                continue;
+            }
+
+            let next = candidates
+                .iter()
+                .enumerate()
+                .map(|(wire, c)| {
+                    if pattern & (1 << wire) != 0 {
+                        c & mask
+                    } else {
+                        c & !mask & all
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            if Self::is_feasible(&next) {
+                self.used[glyph] = true;
+                self.assign_glyph(index + 1, next);
+                self.used[glyph] = false;
+            }
+        }
+    }
+
+    fn assign_wire(
+        &mut self,
+        candidates: &[u32],
+        taken: u32,
+        map: &mut Vec<usize>,
+    ) {
+        if self.solutions.len() >= Self::MAX_SOLUTIONS {
+            return;
+        }
+
+        let wire = map.len();
+        if wire == candidates.len() {
+            self.solutions.push(map.clone());
+            return;
+        }
+
+        let free = candidates[wire] & !taken;
+        for segment in (0..candidates.len()).filter(|s| free & (1 << s) != 0) {
+            map.push(segment);
+            self.assign_wire(candidates, taken | (1 << segment), map);
+            map.pop();
+        }
+    }
+
+    // Cheap check to prune the search early: every wire needs a candidate,
+    // and no two wires can be pinned to the same segment
+    fn is_feasible(candidates: &[u32]) -> bool {
+        let mut pinned = 0u32;
+
+        candidates.iter().all(|&c| {
+            if c.count_ones() == 1 {
+                let overlap = pinned & c != 0;
+                pinned |= c;
+                c != 0 && !overlap
+            } else {
+                c != 0
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [&str; 10] = [
+        "acedgfb", "cdfbe", "gcdfa", "fbcad", "dab", "cefabd", "cdfgeb",
+        "eafb", "cagedb", "ab",
+    ];
+
+    fn scramble(glyphs: &GlyphSet, wiring: &Wiring) -> Vec<String> {
+        // Inverse of the wiring: segment -> wire
+        let mut inverse = vec![0; wiring.map().len()];
+        for (wire, segment) in wiring.map().iter().enumerate() {
+            inverse[*segment] = wire;
+        }
+
+        (0..glyphs.len())
+            .map(|g| {
+                (0..glyphs.segments())
+                    .filter(|s| glyphs.mask(g) & (1 << s) != 0)
+                    .map(|s| (b'a' + inverse[s] as u8) as char)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_solve_example() {
+        let digits = GlyphSet::seven_segment_digits();
+        let wiring = Wiring::solve(&digits, &EXAMPLE).unwrap();
+
+        let decoded = ["cdfeb", "fcadb", "cdfeb", "cdbaf"]
+            .iter()
+            .map(|s| digits.label(wiring.decode(&digits, s).unwrap()))
+            .collect::<String>();
+        assert_eq!(decoded, "5353");
+    }
+
+    #[test]
+    fn test_solve_hex() {
+        let hex = GlyphSet::seven_segment_hex();
+        let expected = Wiring::from_map(vec![3, 6, 0, 5, 1, 4, 2]);
+        let signals = scramble(&hex, &expected);
+
+        assert_eq!(Wiring::solve(&hex, &signals), Ok(expected));
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        let digits = GlyphSet::seven_segment_digits();
+
+        assert_eq!(
+            Wiring::solve(&digits, &EXAMPLE[..2]),
+            Err(DecodeError::Ambiguous)
+        );
+        assert_eq!(
+            Wiring::solve(&digits, &["ab", "abc", "abd"]),
+            Err(DecodeError::Inconsistent)
+        );
+        assert_eq!(
+            Wiring::solve(&digits, &["abz"]),
+            Err(DecodeError::InvalidWire('z'))
+        );
+    }
+}
@@ -0,0 +1,177 @@
+mod disjoint;
+
+use disjoint::DisjointSet;
+use grid::Grid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Basin<T> {
+    pub id: usize,
+    // Lowest cell of the basin (first one in row major order on ties)
+    pub low_point: (usize, usize),
+    pub depth: T,
+    // The lowest height is shared by more than one cell, i.e. the basin has
+    // no strict minimum
+    pub plateau: bool,
+    pub size: usize,
+    // Top left and bottom right corners (inclusive)
+    pub bounds: ((usize, usize), (usize, usize)),
+    // Lowest wall cell around the basin, i.e. the height at which it would
+    // overflow. None if the basin is only bounded by the edges of the map
+    pub rim: Option<T>,
+}
+
+// Connected-component labelling of a heightmap. Every cell lower than `wall`
+// belongs to exactly one basin, and walls belong to none
+#[derive(Debug, Clone)]
+pub struct BasinMap<T> {
+    pub labels: Grid<Option<usize>>,
+    pub basins: Vec<Basin<T>>,
+}
+
+impl<T> BasinMap<T>
+where
+    T: PartialOrd + Copy,
+{
+    pub fn label(grid: &Grid<T>, wall: T) -> Self {
+        let (rows, cols) = (grid.rows(), grid.cols());
+        let is_open = |r: usize, c: usize| grid[r][c] < wall;
+
+        // Join every open cell with its open neighbours to the right and below.
+        // Neighbours to the left and above were already joined by them
+        let mut set = DisjointSet::new(rows * cols);
+        for r in 0..rows {
+            for c in (0..cols).filter(|&c| is_open(r, c)) {
+                if (c + 1 < cols) && is_open(r, c + 1) {
+                    set.union(r * cols + c, r * cols + c + 1);
+                }
+
+                if (r + 1 < rows) && is_open(r + 1, c) {
+                    set.union(r * cols + c, (r + 1) * cols + c);
+                }
+            }
+        }
+
+        // Basins are numbered in the order their first cell is found
+        let mut ids: Vec<Option<usize>> = vec![None; rows * cols];
+        let mut labels = Grid::init(rows, cols, None);
+        let mut basins: Vec<Basin<T>> = Vec::new();
+
+        for r in 0..rows {
+            for c in (0..cols).filter(|&c| is_open(r, c)) {
+                let root = set.find(r * cols + c);
+                let id = *ids[root].get_or_insert_with(|| {
+                    basins.push(Basin {
+                        id: basins.len(),
+                        low_point: (r, c),
+                        depth: grid[r][c],
+                        plateau: false,
+                        size: 0,
+                        bounds: ((r, c), (r, c)),
+                        rim: None,
+                    });
+                    basins.len() - 1
+                });
+                labels[r][c] = Some(id);
+
+                let basin = &mut basins[id];
+                basin.size += 1;
+                basin.bounds.0 .1 = basin.bounds.0 .1.min(c);
+                basin.bounds.1 .0 = r;
+                basin.bounds.1 .1 = basin.bounds.1 .1.max(c);
+
+                if grid[r][c] < basin.depth {
+                    basin.low_point = (r, c);
+                    basin.depth = grid[r][c];
+                    basin.plateau = false;
+                } else if (grid[r][c] == basin.depth)
+                    && (basin.low_point != (r, c))
+                {
+                    basin.plateau = true;
+                }
+            }
+        }
+
+        // Walls next to a basin are part of its rim
+        for r in 0..rows {
+            for c in (0..cols).filter(|&c| !is_open(r, c)) {
+                for (nr, nc) in Self::neighbours(rows, cols, r, c) {
+                    if let Some(id) = labels[nr][nc] {
+                        let rim = &mut basins[id].rim;
+                        if rim.is_none_or(|h| grid[r][c] < h) {
+                            *rim = Some(grid[r][c]);
+                        }
+                    }
+                }
+            }
+        }
+
+        Self { labels, basins }
+    }
+
+    fn neighbours(
+        rows: usize,
+        cols: usize,
+        r: usize,
+        c: usize,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        [
+            (r.wrapping_sub(1), c),
+            (r + 1, c),
+            (r, c.wrapping_sub(1)),
+            (r, c + 1),
+        ]
+        .into_iter()
+        .filter(move |&(r, c)| (r < rows) && (c < cols))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<u8> {
+        let mut grid = Grid::new(0, 0);
+        for row in [
+            "2199943210",
+            "3987894921",
+            "9856789892",
+            "8767896789",
+            "9899965678",
+        ] {
+            grid.push_row(row.bytes().map(|b| b - b'0').collect());
+        }
+
+        grid
+    }
+
+    #[test]
+    fn test_label_example() {
+        let map = BasinMap::label(&example(), 9);
+
+        assert_eq!(
+            map.basins.iter().map(|b| b.size).collect::<Vec<_>>(),
+            vec![3, 9, 14, 9]
+        );
+        assert_eq!(
+            map.basins.iter().map(|b| b.low_point).collect::<Vec<_>>(),
+            vec![(0, 1), (0, 9), (2, 2), (4, 6)]
+        );
+        assert!(map.basins.iter().all(|b| !b.plateau && b.rim == Some(9)));
+        assert_eq!(map.basins[2].bounds, ((1, 0), (4, 5)));
+        assert_eq!(map.labels[0][9], Some(1));
+        assert_eq!(map.labels[0][2], None);
+    }
+
+    #[test]
+    fn test_label_plateau() {
+        let mut grid = Grid::new(0, 0);
+        grid.push_row(vec![3u8, 3, 9, 1]);
+        grid.push_row(vec![3u8, 4, 9, 2]);
+
+        let map = BasinMap::label(&grid, 9);
+        assert_eq!(map.basins.len(), 2);
+        assert!(map.basins[0].plateau);
+        assert_eq!(map.basins[0].low_point, (0, 0));
+        assert!(!map.basins[1].plateau);
+    }
+}
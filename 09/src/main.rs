@@ -1,4 +1,9 @@
+mod basin;
+
+use basin::BasinMap;
+use common::image::{label_colour, ColourRamp, Image, Rgb};
 use grid::Grid;
+use std::fmt::Display;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
@@ -40,41 +45,52 @@ where
     result
 }
 
-fn calculate_basins<T>(grid: &Grid<T>, hotspots: &Vec<(usize, usize, T)>) -> Vec<usize>
+// Prints the basin of every cell (as its id in base 36, '#' for walls)
+// followed by the details of each basin
+fn print_basins<T>(map: &BasinMap<T>)
 where
-    T: PartialOrd + From<u8>,
+    T: Display,
 {
-    let mut result = vec![0; hotspots.len()];
-    let mut visited: Grid<Option<usize>> = Grid::init(grid.rows(), grid.cols(), None);
-
-    for (index, (r, c, _)) in hotspots.iter().enumerate() {
-        let mut queue: Vec<(usize, usize)> = vec![(*r, *c)];
-
-        while let Some((r, c)) = queue.pop() {
-            if visited[r][c].is_none() && grid[r][c] < T::from(9u8) {
-                result[index] += 1;
-                visited[r][c] = Some(index);
-
-                if r > 0 {
-                    queue.push((r - 1, c))
-                }
+    for r in 0..map.labels.rows() {
+        println!(
+            "{}",
+            map.labels
+                .iter_row(r)
+                .map(|l| match l {
+                    Some(id) =>
+                        std::char::from_digit(*id as u32 % 36, 36).unwrap(),
+                    None => '#',
+                })
+                .collect::<String>()
+        );
+    }
 
-                if r < (grid.rows() - 1) {
-                    queue.push((r + 1, c));
-                }
+    for b in &map.basins {
+        println!(
+            "Basin {}: size={}, low={:?} (depth={}{}), bounds={:?}, rim={}",
+            b.id,
+            b.size,
+            b.low_point,
+            b.depth,
+            if b.plateau { ", plateau" } else { "" },
+            b.bounds,
+            b.rim.as_ref().map_or("-".to_owned(), |h| h.to_string())
+        );
+    }
+}
 
-                if c > 0 {
-                    queue.push((r, c - 1))
-                }
+// Heightmap coloured by altitude, tinted by the basin each cell belongs to
+fn render_basins(grid: &Grid<u8>, map: &BasinMap<u8>) -> Image {
+    let ramp = ColourRamp::terrain();
+    let mut image =
+        Image::from_grid(grid, 8, |h| ramp.scaled(*h as f64, 0.0, 9.0));
 
-                if c < (grid.cols() - 1) {
-                    queue.push((r, c + 1));
-                }
-            }
-        }
+    image.overlay_grid(&map.labels, 0.4, |l| l.map(label_colour));
+    for basin in &map.basins {
+        image.set(basin.low_point.0, basin.low_point.1, Rgb::BLACK);
     }
 
-    result
+    image
 }
 
 fn main() {
@@ -94,8 +110,18 @@ fn main() {
         hotspots.len()
     );
 
-    let mut basins = calculate_basins(&data, &hotspots);
-    basins.sort();
+    let map = BasinMap::label(&data, 9);
+    if common::args::flag("--map") {
+        print_basins(&map);
+    }
+    if let Some(output) = common::args::option("--ppm") {
+        render_basins(&data, &map)
+            .save_ppm(&output)
+            .expect("Error while writing image");
+    }
+
+    let mut basins = map.basins.iter().map(|b| b.size).collect::<Vec<_>>();
+    basins.sort_unstable();
     basins.reverse();
     println!(
         "Problem #2: {} x {} x {} = {}",
@@ -1,77 +1,31 @@
-use phf::phf_map;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+mod scoring;
+mod syntax;
 
-static CHUNK_BOUNDS: phf::Map<char, char> = phf_map! {
-    '(' => ')',
-    '{' => '}',
-    '[' => ']',
-    '<' => '>',
-};
+use scoring::{AocScheme, ScoringScheme, TableScheme};
+use std::fs::File;
+use std::io::BufReader;
+use syntax::{Diagnostic, Grammar, LineChecker};
 
 enum ESyntaxScoring {
     Correct,
     Incomplete(u64),
     Corrupted(u64),
+    Invalid,
 }
 
 impl ESyntaxScoring {
-    fn score(c: char) -> Option<u64> {
-        match c {
-            ')' => Some(3),
-            ']' => Some(57),
-            '}' => Some(1197),
-            '>' => Some(25137),
-            _ => None,
+    fn new(diagnostic: &Diagnostic, scheme: &dyn ScoringScheme) -> Self {
+        match diagnostic {
+            Diagnostic::Complete => Self::Correct,
+            Diagnostic::Corrupted { found, .. } => scheme
+                .corrupted(*found)
+                .map_or(Self::Invalid, Self::Corrupted),
+            Diagnostic::Incomplete { completion } => scheme
+                .completion(completion)
+                .map_or(Self::Invalid, Self::Incomplete),
+            Diagnostic::Unexpected { .. } => Self::Invalid,
         }
     }
-
-    fn new(line: &Vec<char>) -> Self {
-        let mut stack: Vec<char> = Vec::with_capacity(80);
-
-        for c in line {
-            match c {
-                '(' | '[' | '{' | '<' => stack.push(*c),
-                ')' | ']' | '}' | '>' => {
-                    let cc = stack.pop().unwrap();
-                    if c != CHUNK_BOUNDS.get(&cc).unwrap() {
-                        return Self::Corrupted(Self::score(*c).unwrap());
-                    }
-                }
-                _ => panic!("Unexpected character {}", c),
-            }
-        }
-
-        if stack.is_empty() {
-            assert!(false);
-            Self::Correct
-        } else {
-            Self::Incomplete(
-                stack
-                    .iter()
-                    .rev()
-                    .map(|c| match c {
-                        '(' => 1,
-                        '[' => 2,
-                        '{' => 3,
-                        '<' => 4,
-                        _ => panic!("Unexpected character {}", c),
-                    })
-                    .reduce(|a, b| a * 5 + b)
-                    .unwrap(),
-            )
-        }
-    }
-}
-
-fn parse(filepath: &str) -> Vec<Vec<char>> {
-    let file = File::open(filepath).expect("Error while opening cave file");
-    let reader = BufReader::new(file);
-
-    reader
-        .lines()
-        .map(|l| l.unwrap().chars().collect())
-        .collect()
 }
 
 fn main() {
@@ -80,41 +34,98 @@ fn main() {
         .nth(1)
         .expect("Filepath for input not provided");
 
-    // Parse input
-    let data = parse(&filepath);
-    let parsed: Vec<ESyntaxScoring> = data.iter().map(|l| ESyntaxScoring::new(l)).collect();
+    // Lines are checked as they are read, so only the scores are kept
+    let file = File::open(&filepath).expect("Error while opening file");
+    let grammar = Grammar::chunks();
+    let json = common::args::flag("--json");
+    let verbose = common::args::flag("--diagnose");
 
-    // Problem #1
-    println!(
-        "Problem #1: {:?}",
-        parsed
-            .iter()
-            .map(|l| if let ESyntaxScoring::Corrupted(s) = l {
-                *s
-            } else {
-                0
-            })
-            .sum::<u64>()
-    );
+    // Scores follow the navigation subsystem rules, unless a scheme is given
+    let scheme: Box<dyn ScoringScheme> = match common::args::option("--scoring")
+    {
+        Some(path) => Box::new(
+            std::fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|s| TableScheme::parse(&s).map_err(|e| e.to_string()))
+                .expect("Error while reading scoring scheme"),
+        ),
+        None => Box::new(AocScheme),
+    };
 
-    // Problem #2
-    let mut scores_incomplete = parsed
-        .iter()
-        .filter_map(|l| {
-            if let ESyntaxScoring::Incomplete(s) = l {
-                Some(s)
-            } else {
-                None
+    // Totals are folded as lines are checked
+    let mut lines = 0;
+    let mut total_corrupted = scheme.total_corrupted();
+    let mut total_incomplete = scheme.total_incomplete();
+
+    for report in LineChecker::new(&grammar, BufReader::new(file), json) {
+        let report = report.expect("Error while reading file");
+        let scoring = ESyntaxScoring::new(&report.diagnostic, scheme.as_ref());
+
+        lines += 1;
+        match scoring {
+            ESyntaxScoring::Corrupted(s) => total_corrupted.push(s),
+            ESyntaxScoring::Incomplete(s) => total_incomplete.push(s),
+            _ => (),
+        }
+
+        if json {
+            let score = match scoring {
+                ESyntaxScoring::Corrupted(s)
+                | ESyntaxScoring::Incomplete(s) => Some(s),
+                _ => None,
+            };
+            println!("{}", syntax::json::line_report(&report, score));
+            continue;
+        }
+
+        // Lines that are not part of the language are always reported, as
+        // they are not scored
+        match report.diagnostic {
+            Diagnostic::Unexpected { position, found } => println!(
+                "Line {}: unexpected character '{}' at {}",
+                report.line,
+                found,
+                position + 1
+            ),
+            Diagnostic::Corrupted {
+                position,
+                expected,
+                found,
+            } if verbose => println!(
+                "Line {}: expected {}, but found '{}' at {}",
+                report.line,
+                expected.map_or(
+                    "an opening delimiter".to_owned(),
+                    |e| format!("'{}'", e)
+                ),
+                found,
+                position + 1
+            ),
+            Diagnostic::Incomplete { completion } if verbose => {
+                println!(
+                    "Line {}: incomplete, complete with {}",
+                    report.line, completion
+                )
             }
-        })
-        .collect::<Vec<_>>();
-    scores_incomplete.sort_unstable();
+            _ => (),
+        }
+    }
+
+    if json {
+        return;
+    }
 
-    assert!(scores_incomplete.len() % 2 == 1);
+    // Problem #1
+    let total =
+        |score: Option<u64>| score.map_or("-".to_owned(), |s| s.to_string());
+    println!("Problem #1: {}", total(total_corrupted.finish()));
+
+    // Problem #2
+    let incomplete = total_incomplete.len();
     println!(
-        "Problem #2: {:?}, size={}/{}",
-        scores_incomplete[scores_incomplete.len() / 2],
-        scores_incomplete.len(),
-        data.len()
+        "Problem #2: {}, size={}/{}",
+        total(total_incomplete.finish()),
+        incomplete,
+        lines
     );
 }
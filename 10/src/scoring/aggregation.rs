@@ -0,0 +1,119 @@
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aggregation {
+    Sum,
+    // Middle score once sorted (the upper one for an even number of scores)
+    Median,
+    // Nearest-rank percentile, in [0, 100]
+    Percentile(f64),
+    Max,
+}
+
+impl Aggregation {
+    pub fn total(self) -> Total {
+        Total {
+            aggregation: self,
+            count: 0,
+            value: (self == Self::Sum).then_some(0),
+            scores: Vec::new(),
+        }
+    }
+}
+
+// Aggregation of scores as they are pushed. Scores are only kept when their
+// rank is needed (i.e. for medians and percentiles)
+#[derive(Debug, Clone)]
+pub struct Total {
+    aggregation: Aggregation,
+    count: usize,
+    // Sum (None once it overflows) or maximum so far
+    value: Option<u64>,
+    scores: Vec<u64>,
+}
+
+impl Total {
+    pub fn push(&mut self, score: u64) {
+        self.count += 1;
+
+        match self.aggregation {
+            Aggregation::Sum => {
+                self.value = self.value.and_then(|v| v.checked_add(score))
+            }
+            Aggregation::Max => {
+                self.value = self.value.max(Some(score));
+            }
+            Aggregation::Median | Aggregation::Percentile(_) => {
+                self.scores.push(score)
+            }
+        }
+    }
+
+    // Number of scores pushed
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    // Outputs None if there are no scores to pick from (or the sum overflows)
+    pub fn finish(mut self) -> Option<u64> {
+        let scores = &mut self.scores;
+        match self.aggregation {
+            Aggregation::Sum | Aggregation::Max => self.value,
+            _ if scores.is_empty() => None,
+            Aggregation::Median => {
+                scores.sort_unstable();
+                Some(scores[scores.len() / 2])
+            }
+            Aggregation::Percentile(p) => {
+                scores.sort_unstable();
+                let rank = (p.clamp(0.0, 100.0) / 100.0 * scores.len() as f64)
+                    .ceil() as usize;
+                Some(scores[rank.saturating_sub(1)])
+            }
+        }
+    }
+}
+
+// e.g. "sum", "median", "max" or "percentile 90"
+impl FromStr for Aggregation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+
+        match (words.next(), words.next(), words.next()) {
+            (Some("sum"), None, None) => Ok(Self::Sum),
+            (Some("median"), None, None) => Ok(Self::Median),
+            (Some("max"), None, None) => Ok(Self::Max),
+            (Some("percentile"), Some(p), None) => match p.parse::<f64>() {
+                Ok(p) if (0.0..=100.0).contains(&p) => Ok(Self::Percentile(p)),
+                _ => Err(format!("invalid percentile '{}'", p)),
+            },
+            _ => Err(format!("unknown aggregation '{}'", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(aggregation: Aggregation, scores: &[u64]) -> Option<u64> {
+        let mut total = aggregation.total();
+        scores.iter().for_each(|s| total.push(*s));
+        total.finish()
+    }
+
+    #[test]
+    fn test_totals() {
+        let scores = [5, 1, 4, 3];
+        assert_eq!(apply(Aggregation::Sum, &scores), Some(13));
+        assert_eq!(apply(Aggregation::Max, &scores), Some(5));
+        assert_eq!(apply(Aggregation::Median, &scores), Some(4));
+        assert_eq!(apply(Aggregation::Percentile(25.0), &scores), Some(1));
+
+        assert_eq!(apply(Aggregation::Sum, &[]), Some(0));
+        assert_eq!(apply(Aggregation::Median, &[]), None);
+        assert_eq!(apply(Aggregation::Sum, &[u64::MAX, 1, 0]), None);
+    }
+}
@@ -0,0 +1,64 @@
+mod aggregation;
+mod table;
+
+pub use aggregation::{Aggregation, Total};
+pub use table::TableScheme;
+
+// Turns syntax errors into scores, and the scores of all lines into a total
+pub trait ScoringScheme {
+    // Penalty of a corrupted line, given the illegal closing delimiter
+    fn corrupted(&self, found: char) -> Option<u64>;
+
+    // Score of an incomplete line, given the characters that complete it
+    fn completion(&self, completion: &str) -> Option<u64>;
+
+    fn total_corrupted(&self) -> Total {
+        Aggregation::Sum.total()
+    }
+
+    fn total_incomplete(&self) -> Total {
+        Aggregation::Median.total()
+    }
+}
+
+// Scoring used by the submarine navigation subsystem
+pub struct AocScheme;
+
+impl ScoringScheme for AocScheme {
+    fn corrupted(&self, found: char) -> Option<u64> {
+        match found {
+            ')' => Some(3),
+            ']' => Some(57),
+            '}' => Some(1197),
+            '>' => Some(25137),
+            _ => None,
+        }
+    }
+
+    fn completion(&self, completion: &str) -> Option<u64> {
+        completion.chars().try_fold(0u64, |acc, c| {
+            let score = match c {
+                ')' => 1,
+                ']' => 2,
+                '}' => 3,
+                '>' => 4,
+                _ => return None,
+            };
+
+            acc.checked_mul(5)?.checked_add(score)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aoc_scheme() {
+        assert_eq!(AocScheme.corrupted('}'), Some(1197));
+        assert_eq!(AocScheme.completion("}}]])})]"), Some(288957));
+        assert_eq!(AocScheme.completion(")x"), None);
+        assert_eq!(AocScheme.completion(&">".repeat(28)), None);
+    }
+}
@@ -0,0 +1,161 @@
+use super::{Aggregation, ScoringScheme, Total};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemeError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SchemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for SchemeError {}
+
+// Scoring scheme defined by penalty tables, usually read from a file such as:
+//
+// [corrupted]
+// ) = 3
+// [completion]
+// base = 5
+// ) = 1
+// [aggregate]
+// corrupted = sum
+// incomplete = percentile 50
+//
+// Empty lines and lines starting with '#' are ignored
+#[derive(Debug, Clone)]
+pub struct TableScheme {
+    corrupted: HashMap<char, u64>,
+    completion: HashMap<char, u64>,
+    base: u64,
+    aggregate_corrupted: Aggregation,
+    aggregate_incomplete: Aggregation,
+}
+
+impl TableScheme {
+    pub fn parse(contents: &str) -> Result<Self, SchemeError> {
+        let mut result = Self {
+            corrupted: HashMap::new(),
+            completion: HashMap::new(),
+            base: 5,
+            aggregate_corrupted: Aggregation::Sum,
+            aggregate_incomplete: Aggregation::Median,
+        };
+        let mut section = "";
+
+        for (index, line) in contents.lines().enumerate() {
+            let error = |message: String| SchemeError {
+                line: index + 1,
+                message,
+            };
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) =
+                line.strip_prefix('[').and_then(|l| l.strip_suffix(']'))
+            {
+                section = match name.trim() {
+                    "corrupted" => "corrupted",
+                    "completion" => "completion",
+                    "aggregate" => "aggregate",
+                    s => return Err(error(format!("unknown section '{}'", s))),
+                };
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .map(|(k, v)| (k.trim(), v.trim()))
+                .ok_or_else(|| error("expected 'key = value'".to_owned()))?;
+            let number = || {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| error(format!("invalid number '{}'", value)))
+            };
+            let aggregation = || value.parse::<Aggregation>().map_err(error);
+
+            match (section, key) {
+                ("completion", "base") => result.base = number()?,
+                ("aggregate", "corrupted") => {
+                    result.aggregate_corrupted = aggregation()?
+                }
+                ("aggregate", "incomplete") => {
+                    result.aggregate_incomplete = aggregation()?
+                }
+                ("corrupted" | "completion", k) if k.chars().count() == 1 => {
+                    let c = k.chars().next().unwrap();
+                    let table = match section {
+                        "corrupted" => &mut result.corrupted,
+                        _ => &mut result.completion,
+                    };
+                    table.insert(c, number()?);
+                }
+                ("", _) => {
+                    return Err(error("key outside of a section".to_owned()))
+                }
+                (_, k) => return Err(error(format!("unexpected key '{}'", k))),
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl ScoringScheme for TableScheme {
+    fn corrupted(&self, found: char) -> Option<u64> {
+        self.corrupted.get(&found).copied()
+    }
+
+    fn completion(&self, completion: &str) -> Option<u64> {
+        completion.chars().try_fold(0u64, |acc, c| {
+            acc.checked_mul(self.base)?
+                .checked_add(*self.completion.get(&c)?)
+        })
+    }
+
+    fn total_corrupted(&self) -> Total {
+        self.aggregate_corrupted.total()
+    }
+
+    fn total_incomplete(&self) -> Total {
+        self.aggregate_incomplete.total()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::AocScheme;
+    use super::*;
+
+    #[test]
+    fn test_parse_matches_aoc() {
+        let scheme =
+            TableScheme::parse(include_str!("../../data/scoring.ini")).unwrap();
+
+        for c in [')', ']', '}', '>', 'x'] {
+            assert_eq!(scheme.corrupted(c), AocScheme.corrupted(c));
+        }
+        assert_eq!(scheme.completion("}}]])})]"), Some(288957));
+        let mut total = scheme.total_incomplete();
+        [5, 1, 3].into_iter().for_each(|s| total.push(s));
+        assert_eq!(total.finish(), Some(3));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            TableScheme::parse("[corrupted]\n) = x").unwrap_err().line,
+            2
+        );
+        assert!(TableScheme::parse(") = 3").is_err());
+        assert!(TableScheme::parse("[aggregate]\nincomplete = mean").is_err());
+    }
+}
@@ -0,0 +1,66 @@
+// Describes the characters of a bracket language:
+// * pairs of opening and closing delimiters, which must nest properly
+// * characters that are skipped (e.g. whitespace)
+// * quote characters, whose contents are not validated until the same quote
+//   character closes them, and an escape character valid inside quotes
+#[derive(Debug, Clone)]
+pub struct Grammar {
+    pairs: Vec<(char, char)>,
+    ignored: Vec<char>,
+    quotes: Vec<char>,
+    escape: Option<char>,
+}
+
+impl Grammar {
+    pub fn new(pairs: &[(char, char)]) -> Self {
+        Self {
+            pairs: pairs.to_vec(),
+            ignored: Vec::new(),
+            quotes: Vec::new(),
+            escape: None,
+        }
+    }
+
+    // The four chunk delimiters of the submarine navigation subsystem
+    pub fn chunks() -> Self {
+        Self::new(&[('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')])
+    }
+
+    #[allow(dead_code)]
+    pub fn with_ignored(mut self, ignored: &[char]) -> Self {
+        self.ignored.extend_from_slice(ignored);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_quotes(mut self, quotes: &[char]) -> Self {
+        self.quotes.extend_from_slice(quotes);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_escape(mut self, escape: char) -> Self {
+        self.escape = Some(escape);
+        self
+    }
+
+    pub fn closing(&self, open: char) -> Option<char> {
+        self.pairs.iter().find(|(o, _)| *o == open).map(|(_, c)| *c)
+    }
+
+    pub fn is_closing(&self, c: char) -> bool {
+        self.pairs.iter().any(|(_, close)| *close == c)
+    }
+
+    pub fn is_ignored(&self, c: char) -> bool {
+        self.ignored.contains(&c)
+    }
+
+    pub fn is_quote(&self, c: char) -> bool {
+        self.quotes.contains(&c)
+    }
+
+    pub fn is_escape(&self, c: char) -> bool {
+        self.escape == Some(c)
+    }
+}
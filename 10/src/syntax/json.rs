@@ -0,0 +1,131 @@
+use super::{Chunk, Diagnostic, LineReport};
+use common::json::string;
+use std::fmt::Write;
+
+// Renders a line report as a single line JSON object (i.e. JSON Lines when
+// printing one report per line). `score` is added as is, or null
+pub fn line_report(report: &LineReport, score: Option<u64>) -> String {
+    let mut result = String::new();
+
+    write!(result, "{{\"line\":{},", report.line).unwrap();
+    match &report.diagnostic {
+        Diagnostic::Complete => result.push_str("\"status\":\"complete\","),
+        Diagnostic::Incomplete { completion } => write!(
+            result,
+            "\"status\":\"incomplete\",\"completion\":{},",
+            string(completion)
+        )
+        .unwrap(),
+        Diagnostic::Corrupted {
+            position,
+            expected,
+            found,
+        } => write!(
+            result,
+            "\"status\":\"corrupted\",\"position\":{},\"expected\":{},\"found\":{},",
+            position,
+            expected.map_or("null".to_owned(), |e| string(&e.to_string())),
+            string(&found.to_string())
+        )
+        .unwrap(),
+        Diagnostic::Unexpected { position, found } => write!(
+            result,
+            "\"status\":\"unexpected\",\"position\":{},\"found\":{},",
+            position,
+            string(&found.to_string())
+        )
+        .unwrap(),
+    }
+
+    write!(
+        result,
+        "\"score\":{},\"max_depth\":{},\"chunks\":",
+        score.map_or("null".to_owned(), |s| s.to_string()),
+        report.max_depth
+    )
+    .unwrap();
+    chunks(&mut result, &report.chunks);
+    result.push('}');
+
+    result
+}
+
+// Chunk trees are walked with an explicit stack, as lines can be nested
+// deeper than the call stack allows
+fn chunks(output: &mut String, chunks: &[Chunk]) {
+    let mut stack = vec![chunks.iter()];
+    let mut first = true;
+
+    output.push('[');
+    while let Some(siblings) = stack.last_mut() {
+        match siblings.next() {
+            Some(chunk) => {
+                if !first {
+                    output.push(',');
+                }
+
+                write!(
+                    output,
+                    "{{\"open\":{},\"start\":{},\"end\":{},\"children\":[",
+                    string(&chunk.open.to_string()),
+                    chunk.start,
+                    chunk.end.map_or("null".to_owned(), |e| e.to_string())
+                )
+                .unwrap();
+                stack.push(chunk.children.iter());
+                first = true;
+            }
+            None => {
+                stack.pop();
+                output.push(']');
+                if !stack.is_empty() {
+                    output.push('}');
+                }
+                first = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Grammar, LineChecker};
+    use super::*;
+
+    fn report(line: &str) -> LineReport {
+        let grammar = Grammar::chunks();
+        LineChecker::new(&grammar, line.as_bytes(), true)
+            .next()
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_line_report() {
+        assert_eq!(
+            line_report(&report("(<>)["), Some(2)),
+            "{\"line\":1,\"status\":\"incomplete\",\"completion\":\"]\",\
+             \"score\":2,\"max_depth\":2,\"chunks\":[\
+             {\"open\":\"(\",\"start\":0,\"end\":3,\"children\":[\
+             {\"open\":\"<\",\"start\":1,\"end\":2,\"children\":[]}]},\
+             {\"open\":\"[\",\"start\":4,\"end\":null,\"children\":[]}]}"
+        );
+        assert_eq!(
+            line_report(&report("(]"), None),
+            "{\"line\":1,\"status\":\"corrupted\",\"position\":1,\
+             \"expected\":\")\",\"found\":\"]\",\"score\":null,\
+             \"max_depth\":1,\"chunks\":[\
+             {\"open\":\"(\",\"start\":0,\"end\":null,\"children\":[]}]}"
+        );
+    }
+
+    #[test]
+    fn test_deep_line() {
+        let depth = 200_000;
+        let line = "(".repeat(depth) + &")".repeat(depth);
+        let json = line_report(&report(&line), None);
+
+        assert!(json.contains(&format!("\"max_depth\":{},", depth)));
+        assert!(json.ends_with(&"]}".repeat(depth + 1)));
+    }
+}
@@ -0,0 +1,8 @@
+mod grammar;
+pub mod json;
+mod stream;
+mod validator;
+
+pub use grammar::Grammar;
+pub use stream::{LineChecker, LineReport};
+pub use validator::{Chunk, Diagnostic, Validator};
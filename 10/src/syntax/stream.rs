@@ -0,0 +1,141 @@
+use super::{Chunk, Diagnostic, Grammar, Validator};
+use std::io::BufRead;
+
+#[derive(Debug, Clone)]
+pub struct LineReport {
+    // Line number, starting at 1
+    pub line: usize,
+    pub diagnostic: Diagnostic,
+    pub max_depth: usize,
+    pub chunks: Vec<Chunk>,
+}
+
+// Validates every line of a byte stream, as it is being read. Only one line
+// is kept in memory at a time, and the input is never stored as a whole
+pub struct LineChecker<'a, R> {
+    grammar: &'a Grammar,
+    reader: R,
+    buffer: Vec<u8>,
+    line: usize,
+    track_chunks: bool,
+}
+
+impl<'a, R: BufRead> LineChecker<'a, R> {
+    pub fn new(grammar: &'a Grammar, reader: R, track_chunks: bool) -> Self {
+        Self {
+            grammar,
+            reader,
+            buffer: Vec::new(),
+            line: 0,
+            track_chunks,
+        }
+    }
+
+    fn check(&self, line: &str) -> LineReport {
+        let mut validator = Validator::new(self.grammar, self.track_chunks);
+        let diagnostic = validator.check(line);
+
+        LineReport {
+            line: self.line,
+            diagnostic,
+            max_depth: validator.max_depth(),
+            chunks: validator.into_chunks(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for LineChecker<'_, R> {
+    type Item = std::io::Result<LineReport>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.clear();
+        match self.reader.read_until(b'\n', &mut self.buffer) {
+            Ok(0) => None,
+            Ok(_) => {
+                self.line += 1;
+                let line = String::from_utf8_lossy(&self.buffer);
+                Some(Ok(self.check(line.trim_end_matches(['\n', '\r']))))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufReader, Read};
+
+    // Reader that hands out the input a few bytes at a time, and counts how
+    // many bytes were read
+    struct Trickle<'a> {
+        input: &'a [u8],
+        read: std::rc::Rc<std::cell::Cell<usize>>,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(self.input.len()).min(3);
+            buf[..n].copy_from_slice(&self.input[..n]);
+            self.input = &self.input[n..];
+            self.read.set(self.read.get() + n);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_line_checker() {
+        let grammar = Grammar::chunks();
+        let input = "[]\r\n(]\n\n{<\n<a>";
+        let read = std::rc::Rc::new(std::cell::Cell::new(0));
+        let reader = BufReader::with_capacity(
+            4,
+            Trickle {
+                input: input.as_bytes(),
+                read: read.clone(),
+            },
+        );
+        let mut checker = LineChecker::new(&grammar, reader, false);
+
+        // Lines are checked as soon as they are read
+        let report = checker.next().unwrap().unwrap();
+        assert_eq!((report.line, report.diagnostic), (1, Diagnostic::Complete));
+        assert!(read.get() < input.len());
+
+        let diagnostics = checker
+            .map(|r| r.map(|r| (r.line, r.diagnostic, r.chunks.len())))
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            diagnostics,
+            vec![
+                (
+                    2,
+                    Diagnostic::Corrupted {
+                        position: 1,
+                        expected: Some(')'),
+                        found: ']'
+                    },
+                    0
+                ),
+                (3, Diagnostic::Complete, 0),
+                (
+                    4,
+                    Diagnostic::Incomplete {
+                        completion: ">}".to_owned()
+                    },
+                    0
+                ),
+                (
+                    5,
+                    Diagnostic::Unexpected {
+                        position: 1,
+                        found: 'a'
+                    },
+                    0
+                ),
+            ]
+        );
+        assert_eq!(read.get(), input.len());
+    }
+}
@@ -0,0 +1,269 @@
+use super::Grammar;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    // Every chunk was closed
+    Complete,
+    // Line ended with open chunks (or an open quote). `completion` is the
+    // sequence of characters that closes all of them
+    Incomplete {
+        completion: String,
+    },
+    // A closing delimiter does not match the innermost open chunk. `expected`
+    // is None when there was no open chunk to close
+    Corrupted {
+        position: usize,
+        expected: Option<char>,
+        found: char,
+    },
+    // Character is not part of the grammar
+    Unexpected {
+        position: usize,
+        found: char,
+    },
+}
+
+// Span of a pair of delimiters, where positions are character indices.
+// `end` is None while (or if) the chunk is not closed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    pub open: char,
+    pub start: usize,
+    pub end: Option<usize>,
+    pub children: Vec<Chunk>,
+}
+
+// Children are dropped one level at a time, so that dropping the tree of a
+// deeply nested line does not overflow the stack
+impl Drop for Chunk {
+    fn drop(&mut self) {
+        let mut stack = std::mem::take(&mut self.children);
+        while let Some(mut chunk) = stack.pop() {
+            stack.append(&mut chunk.children);
+        }
+    }
+}
+
+// Incremental validation of a line, one character at a time. Memory usage is
+// bounded by the nesting depth, unless the chunk tree is being tracked
+pub struct Validator<'a> {
+    grammar: &'a Grammar,
+    // Open chunks, innermost last, with the closing delimiter they expect
+    stack: Vec<(char, Chunk)>,
+    // Top level chunks already closed
+    roots: Vec<Chunk>,
+    track_chunks: bool,
+    quote: Option<char>,
+    escaped: bool,
+    position: usize,
+    max_depth: usize,
+}
+
+impl<'a> Validator<'a> {
+    pub fn new(grammar: &'a Grammar, track_chunks: bool) -> Self {
+        Self {
+            grammar,
+            stack: Vec::new(),
+            roots: Vec::new(),
+            track_chunks,
+            quote: None,
+            escaped: false,
+            position: 0,
+            max_depth: 0,
+        }
+    }
+
+    // Consumes the next character of the line. Outputs a diagnostic as soon as
+    // the line is known to be invalid, after which no more characters should
+    // be pushed
+    pub fn push(&mut self, c: char) -> Option<Diagnostic> {
+        let position = self.position;
+        self.position += 1;
+
+        if let Some(q) = self.quote {
+            if self.escaped {
+                self.escaped = false;
+            } else if self.grammar.is_escape(c) {
+                self.escaped = true;
+            } else if c == q {
+                self.quote = None;
+            }
+        } else if let Some(close) = self.grammar.closing(c) {
+            self.stack.push((
+                close,
+                Chunk {
+                    open: c,
+                    start: position,
+                    end: None,
+                    children: Vec::new(),
+                },
+            ));
+            self.max_depth = self.max_depth.max(self.stack.len());
+        } else if self.grammar.is_closing(c) {
+            let expected = self.stack.last().map(|(close, _)| *close);
+            if expected != Some(c) {
+                return Some(Diagnostic::Corrupted {
+                    position,
+                    expected,
+                    found: c,
+                });
+            }
+
+            let (_, mut chunk) = self.stack.pop().unwrap();
+            chunk.end = Some(position);
+            self.attach(chunk);
+        } else if self.grammar.is_quote(c) {
+            self.quote = Some(c);
+        } else if !self.grammar.is_ignored(c) {
+            return Some(Diagnostic::Unexpected { position, found: c });
+        }
+
+        None
+    }
+
+    // Pushes a whole line, and outputs its diagnostic
+    pub fn check(&mut self, line: &str) -> Diagnostic {
+        line.chars()
+            .find_map(|c| self.push(c))
+            .unwrap_or_else(|| self.finish())
+    }
+
+    // Diagnostic of the line, assuming it ended with the last pushed character
+    pub fn finish(&self) -> Diagnostic {
+        if self.stack.is_empty() && self.quote.is_none() {
+            Diagnostic::Complete
+        } else {
+            Diagnostic::Incomplete {
+                completion: self
+                    .quote
+                    .into_iter()
+                    .chain(self.stack.iter().rev().map(|(close, _)| *close))
+                    .collect(),
+            }
+        }
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    // Chunk tree of the line (empty if not tracked). Chunks still open are
+    // included with no end
+    pub fn into_chunks(mut self) -> Vec<Chunk> {
+        while let Some((_, chunk)) = self.stack.pop() {
+            self.attach(chunk);
+        }
+
+        self.roots
+    }
+
+    fn attach(&mut self, chunk: Chunk) {
+        if !self.track_chunks {
+            return;
+        }
+
+        match self.stack.last_mut() {
+            Some((_, parent)) => parent.children.push(chunk),
+            None => self.roots.push(chunk),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(grammar: &Grammar, line: &str) -> Diagnostic {
+        Validator::new(grammar, false).check(line)
+    }
+
+    #[test]
+    fn test_validate_chunks() {
+        let grammar = Grammar::chunks();
+
+        assert_eq!(validate(&grammar, "([]){<>}"), Diagnostic::Complete);
+        assert_eq!(
+            validate(&grammar, "[({(<(())[]>[[{[]{<()<>>"),
+            Diagnostic::Incomplete {
+                completion: "}}]])})]".to_owned()
+            }
+        );
+        assert_eq!(
+            validate(&grammar, "{([(<{}[<>[]}>{[]{[(<()>"),
+            Diagnostic::Corrupted {
+                position: 12,
+                expected: Some(']'),
+                found: '}'
+            }
+        );
+        assert_eq!(
+            validate(&grammar, "())"),
+            Diagnostic::Corrupted {
+                position: 2,
+                expected: None,
+                found: ')'
+            }
+        );
+        assert_eq!(
+            validate(&grammar, "(a)"),
+            Diagnostic::Unexpected {
+                position: 1,
+                found: 'a'
+            }
+        );
+    }
+
+    #[test]
+    fn test_validator_chunks() {
+        let grammar = Grammar::chunks();
+        let mut validator = Validator::new(&grammar, true);
+
+        assert!("([]<)".chars().find_map(|c| validator.push(c)).is_some());
+        assert_eq!(validator.max_depth(), 2);
+
+        let mut validator = Validator::new(&grammar, true);
+        assert!("([]<>)[".chars().find_map(|c| validator.push(c)).is_none());
+        assert_eq!(validator.max_depth(), 2);
+
+        let chunk = |open, start, end, children| Chunk {
+            open,
+            start,
+            end,
+            children,
+        };
+        assert_eq!(
+            validator.into_chunks(),
+            vec![
+                chunk(
+                    '(',
+                    0,
+                    Some(5),
+                    vec![
+                        chunk('[', 1, Some(2), vec![]),
+                        chunk('<', 3, Some(4), vec![])
+                    ]
+                ),
+                chunk('[', 6, None, vec![])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_quotes() {
+        let grammar = Grammar::new(&[('{', '}'), ('[', ']')])
+            .with_ignored(&[' ', ',', ':'])
+            .with_quotes(&['"'])
+            .with_escape('\\');
+
+        assert_eq!(
+            validate(&grammar, r#"{"a}": ["\"]", "b"]}"#),
+            Diagnostic::Complete
+        );
+        assert_eq!(
+            validate(&grammar, r#"{"a": ["b\""#),
+            Diagnostic::Incomplete {
+                completion: "\"]}".to_owned()
+            }
+        );
+    }
+}
@@ -1,14 +1,77 @@
+use common::image::{ColourRamp, Image, Rgb};
+use common::terminal::{grid_frame, Animation, Control};
 use grid::Grid;
+use std::collections::HashMap;
+use std::fmt;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
+// Octopuses whose energy is raised by a flash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Neighbourhood {
+    // All 8 surrounding octopuses, including diagonals
+    Moore,
+    // Only the 4 octopuses above, below, left and right
+    VonNeumann,
+}
+
+impl Neighbourhood {
+    fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Self::Moore => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+            Self::VonNeumann => &[(-1, 0), (0, -1), (0, 1), (1, 0)],
+        }
+    }
+}
+
+// States repeat every `period` steps, starting at generation `start`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cycle {
+    start: usize,
+    period: usize,
+}
+
 #[derive(Debug)]
+enum SimulationError {
+    StepLimit(usize),
+    // A state repeated before all octopuses flashed at once, so they never
+    // will
+    NeverSynchronizes(Cycle),
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::StepLimit(steps) => {
+                write!(f, "no result after {} steps", steps)
+            }
+            Self::NeverSynchronizes(cycle) => write!(
+                f,
+                "never synchronizes (cycle of {} steps from generation {})",
+                cycle.period, cycle.start
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 struct EnergyGrid {
     state: Grid<u8>,
     length: usize,
     queue: Vec<(usize, usize)>,
     flashes: usize,
     generation: usize,
+    threshold: u8,
+    neighbourhood: Neighbourhood,
 }
 
 impl EnergyGrid {
@@ -21,9 +84,23 @@ impl EnergyGrid {
             queue,
             flashes: 0,
             generation: 0,
+            threshold: 9,
+            neighbourhood: Neighbourhood::Moore,
         }
     }
 
+    // Octopuses flash when their energy goes above `threshold`
+    fn with_threshold(mut self, threshold: u8) -> Self {
+        debug_assert!(threshold < u8::MAX);
+        self.threshold = threshold;
+        self
+    }
+
+    fn with_neighbourhood(mut self, neighbourhood: Neighbourhood) -> Self {
+        self.neighbourhood = neighbourhood;
+        self
+    }
+
     fn parse(filepath: &str) -> Self {
         let file = File::open(filepath).expect("Error while opening file");
         let reader = BufReader::new(file);
@@ -42,8 +119,8 @@ impl EnergyGrid {
     }
 
     fn increment_octopus(&mut self, row: usize, col: usize) {
-        if self.state[row][col] < 10 {
-            if self.state[row][col] == 9 {
+        if self.state[row][col] <= self.threshold {
+            if self.state[row][col] == self.threshold {
                 self.queue.push((row, col));
             }
             self.state[row][col] += 1;
@@ -60,9 +137,13 @@ impl EnergyGrid {
     }
 
     fn normalize_level(&mut self) -> &mut Self {
-        self.state.iter_mut().filter(|v| **v > 9).for_each(|v| {
-            *v = 0;
-        });
+        let threshold = self.threshold;
+        self.state
+            .iter_mut()
+            .filter(|v| **v > threshold)
+            .for_each(|v| {
+                *v = 0;
+            });
         self
     }
 
@@ -70,8 +151,11 @@ impl EnergyGrid {
         while let Some((r, c)) = self.queue.pop() {
             self.flashes += 1;
 
-            for r in r.saturating_sub(1)..=num::clamp(r + 1, 0, self.state.rows() - 1) {
-                for c in c.saturating_sub(1)..=num::clamp(c + 1, 0, self.state.cols() - 1) {
+            for (dr, dc) in self.neighbourhood.offsets() {
+                let r = r.wrapping_add_signed(*dr);
+                let c = c.wrapping_add_signed(*dc);
+
+                if r < self.state.rows() && c < self.state.cols() {
                     self.increment_octopus(r, c);
                 }
             }
@@ -84,12 +168,99 @@ impl EnergyGrid {
         self.increase_level().resolve_flashes().normalize_level()
     }
 
-    fn syncronize(&mut self) -> &mut Self {
-        let mut total_prev = self.flashes;
-        while (self.step().flashes - total_prev) < self.length {
-            total_prev = self.flashes;
+    fn snapshot(&self) -> Vec<u8> {
+        self.state.iter().copied().collect()
+    }
+
+    // Steps until all octopuses flash at once. Fails if that did not happen
+    // after `max_steps`, or if the states started repeating without it
+    fn syncronize(
+        &mut self,
+        max_steps: usize,
+    ) -> Result<&mut Self, SimulationError> {
+        let mut seen = HashMap::from([(self.snapshot(), self.generation)]);
+
+        for _ in 0..max_steps {
+            let total_prev = self.flashes;
+            if (self.step().flashes - total_prev) == self.length {
+                return Ok(self);
+            }
+
+            if let Some(start) = seen.insert(self.snapshot(), self.generation) {
+                return Err(SimulationError::NeverSynchronizes(Cycle {
+                    start,
+                    period: self.generation - start,
+                }));
+            }
+        }
+
+        Err(SimulationError::StepLimit(max_steps))
+    }
+
+    // Steps until a state is found for the second time
+    fn find_cycle(
+        &mut self,
+        max_steps: usize,
+    ) -> Result<Cycle, SimulationError> {
+        let mut seen = HashMap::from([(self.snapshot(), self.generation)]);
+
+        for _ in 0..max_steps {
+            self.step();
+
+            if let Some(start) = seen.insert(self.snapshot(), self.generation) {
+                return Ok(Cycle {
+                    start,
+                    period: self.generation - start,
+                });
+            }
+        }
+
+        Err(SimulationError::StepLimit(max_steps))
+    }
+
+    // Energy levels as a heat map, where the octopuses that flashed during
+    // the last step (back to level 0) are drawn in white
+    fn render(&self) -> Image {
+        let ramp = ColourRamp::heat();
+        Image::from_grid(&self.state, 16, |v| match v {
+            0 if self.generation > 0 => Rgb::WHITE,
+            _ => ramp.scaled(*v as f64, 0.0, self.threshold as f64 + 3.0),
+        })
+    }
+
+    // Same colours as `render`, with the energy level as the character
+    fn frame(&self) -> String {
+        let ramp = ColourRamp::heat();
+        let grid = grid_frame(&self.state, |v| match v {
+            0 if self.generation > 0 => ('*', Rgb::WHITE),
+            _ => (
+                std::char::from_digit(*v as u32 % 36, 36).unwrap(),
+                ramp.scaled(*v as f64, 0.0, self.threshold as f64 + 3.0),
+            ),
+        });
+
+        format!(
+            "Generation {} (flashes = {})\n{}",
+            self.generation, self.flashes, grid
+        )
+    }
+
+    // Steps (on a copy) until all octopuses flash at once, showing each step
+    fn animate(&self, animation: &mut Animation, max_steps: usize) {
+        let mut grid = self.clone();
+
+        while animation
+            .show(&grid.frame())
+            .expect("Error while animating")
+            == Control::Continue
+        {
+            if grid.generation >= self.generation + max_steps
+                || (grid.generation > 0 && grid.state.iter().all(|v| *v == 0))
+            {
+                break;
+            }
+            grid.step();
         }
-        self
     }
 }
 
@@ -98,11 +269,158 @@ fn main() {
         .nth(1)
         .expect("Filepath for input not provided");
 
-    let mut data = EnergyGrid::parse(&filepath);
+    let option = |name: &str| {
+        common::args::option(name)
+            .map(|v| v.parse::<usize>().expect("Invalid numeric option"))
+    };
+    let max_steps = option("--max-steps").unwrap_or(10_000);
+
+    // Energy levels go one above the threshold, so it must stay below u8::MAX
+    let threshold = match common::args::option("--threshold") {
+        None => 9,
+        Some(t) => match t.parse::<u8>() {
+            Ok(t) if t < u8::MAX => t,
+            _ => panic!("Invalid threshold {} (expected 0 to 254)", t),
+        },
+    };
+
+    let mut data = EnergyGrid::parse(&filepath)
+        .with_threshold(threshold)
+        .with_neighbourhood(match common::args::option("--neighbourhood") {
+            Some(n) if n == "von-neumann" => Neighbourhood::VonNeumann,
+            Some(n) if n != "moore" => panic!(
+                "Unknown neighbourhood {} (expected moore or von-neumann)",
+                n
+            ),
+            _ => Neighbourhood::Moore,
+        });
+
+    if let Some(mut animation) = Animation::from_args() {
+        data.animate(&mut animation, max_steps);
+    }
+
+    if common::args::flag("--cycle") {
+        match data.clone().find_cycle(max_steps) {
+            Ok(c) => {
+                println!("Cycle: period={}, pre-period={}", c.period, c.start)
+            }
+            Err(e) => println!("Cycle: {}", e),
+        }
+    }
 
     (0..100).for_each(|_| {
         data.step();
     });
     println!("Problem #1: {:?}", data.flashes);
-    println!("Problem #2: {:?}", data.syncronize().generation);
+    if let Some(output) = common::args::option("--ppm") {
+        data.render()
+            .save_ppm(&output)
+            .expect("Error while writing image");
+    }
+
+    match data.syncronize(max_steps) {
+        Ok(data) => println!("Problem #2: {:?}", data.generation),
+        Err(e) => println!("Problem #2: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(rows: &[&str]) -> EnergyGrid {
+        let mut state = Grid::new(0, 0);
+        for row in rows {
+            state.push_row(
+                row.chars().map(|c| c.to_digit(10).unwrap() as u8).collect(),
+            );
+        }
+        EnergyGrid::init(state)
+    }
+
+    fn example() -> EnergyGrid {
+        grid(&[
+            "5483143223",
+            "2745854711",
+            "5264556173",
+            "6141336146",
+            "6357385478",
+            "4167524645",
+            "2176841721",
+            "6882881134",
+            "4846848554",
+            "5283751526",
+        ])
+    }
+
+    #[test]
+    fn test_example() {
+        let mut data = example();
+        (0..100).for_each(|_| {
+            data.step();
+        });
+        assert_eq!(data.flashes, 1656);
+        assert_eq!(data.syncronize(1000).unwrap().generation, 195);
+
+        assert!(matches!(
+            example().syncronize(100),
+            Err(SimulationError::StepLimit(100))
+        ));
+    }
+
+    #[test]
+    fn test_threshold() {
+        let mut data = grid(&["0"]).with_threshold(2);
+        data.step().step();
+        assert_eq!((data.state[0][0], data.flashes), (2, 0));
+        data.step();
+        assert_eq!((data.state[0][0], data.flashes), (0, 1));
+
+        // Levels reach 255 before flashing
+        let mut data = grid(&["0"]).with_threshold(254);
+        assert_eq!(data.syncronize(1000).unwrap().generation, 255);
+    }
+
+    #[test]
+    fn test_neighbourhood() {
+        let center = ["000", "090", "000"];
+
+        let mut data = grid(&center);
+        data.step();
+        assert_eq!(data.snapshot(), vec![2, 2, 2, 2, 0, 2, 2, 2, 2]);
+
+        let mut data =
+            grid(&center).with_neighbourhood(Neighbourhood::VonNeumann);
+        data.step();
+        assert_eq!(data.snapshot(), vec![1, 2, 1, 2, 0, 2, 1, 2, 1]);
+    }
+
+    #[test]
+    fn test_cycles() {
+        // The right octopus flashes 5 steps in, and each flash pushes the
+        // other one back to its initial level
+        let data = grid(&["05"]).with_neighbourhood(Neighbourhood::VonNeumann);
+        let cycle = Cycle {
+            start: 0,
+            period: 9,
+        };
+
+        assert_eq!(data.clone().find_cycle(100).unwrap(), cycle);
+        assert!(matches!(
+            data.clone().syncronize(100),
+            Err(SimulationError::NeverSynchronizes(c)) if c == cycle
+        ));
+        assert!(matches!(
+            data.clone().find_cycle(5),
+            Err(SimulationError::StepLimit(5))
+        ));
+
+        assert_eq!(
+            grid(&["0"]).find_cycle(100).unwrap(),
+            Cycle {
+                start: 0,
+                period: 10
+            }
+        );
+    }
 }
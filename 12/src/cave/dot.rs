@@ -0,0 +1,118 @@
+use super::{CaveGraph, PathError, VisitPolicy};
+use common::image::{ColourRamp, Rgb};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// Extra information drawn on top of the cave system
+pub enum Overlay<'a> {
+    None,
+    // Tunnels walked by a path (cave ids, in order) are highlighted
+    Path(&'a [usize]),
+    // Tunnels are coloured (and labelled) by how many times they are walked
+    Traffic(&'a HashMap<(usize, usize), u64>),
+}
+
+impl CaveGraph {
+    // Tunnels are identified by their two caves, lowest id first
+    pub fn tunnel(a: usize, b: usize) -> (usize, usize) {
+        (a.min(b), a.max(b))
+    }
+
+    // Number of times each tunnel is walked, over all the paths allowed by
+    // the policy. Every path is enumerated, so this takes time proportional to
+    // the number of paths, which grows exponentially with the cave system
+    pub fn traffic<P: VisitPolicy>(
+        &self,
+        policy: &P,
+    ) -> Result<HashMap<(usize, usize), u64>, PathError> {
+        let mut result = HashMap::new();
+
+        for path in self.paths(policy)? {
+            for step in path.windows(2) {
+                *result.entry(Self::tunnel(step[0], step[1])).or_insert(0) += 1;
+            }
+        }
+
+        Ok(result)
+    }
+
+    // First pair of consecutive caves of a path without a tunnel between them
+    pub fn missing_tunnel(&self, path: &[usize]) -> Option<(usize, usize)> {
+        path.windows(2)
+            .find(|s| !self.neighbours(s[0]).contains(&s[1]))
+            .map(|s| (s[0], s[1]))
+    }
+
+    // Graphviz description of the cave system: big caves are drawn as filled
+    // boxes, small caves as circles and "start"/"end" as double circles
+    pub fn to_dot(&self, overlay: &Overlay) -> String {
+        let mut result = String::from("graph caves {\n");
+        result.push_str("    node [fontname=\"Helvetica\"];\n");
+
+        for cave in 0..self.len() {
+            let style = if self.is_terminal(cave) {
+                "shape=doublecircle"
+            } else if self.is_small(cave) {
+                "shape=circle"
+            } else {
+                "shape=box, style=filled, fillcolor=\"#9ecae1\""
+            };
+            writeln!(result, "    {} [{}];", quote(self.name(cave)), style)
+                .unwrap();
+        }
+
+        let walked = match overlay {
+            Overlay::Path(path) => {
+                path.windows(2).map(|s| Self::tunnel(s[0], s[1])).collect()
+            }
+            _ => Vec::new(),
+        };
+        let busiest = match overlay {
+            Overlay::Traffic(t) => t.values().copied().max().unwrap_or(0),
+            _ => 0,
+        };
+        let ramp = ColourRamp::heat();
+
+        for a in 0..self.len() {
+            for &b in self.neighbours(a).iter().filter(|&&b| a < b) {
+                let tunnel = Self::tunnel(a, b);
+                let style = match overlay {
+                    Overlay::Path(_) if walked.contains(&tunnel) => {
+                        " [color=\"#e41a1c\", penwidth=3]".to_owned()
+                    }
+                    Overlay::Traffic(traffic) => {
+                        let count = traffic.get(&tunnel).copied().unwrap_or(0);
+                        let t = count as f64 / busiest.max(1) as f64;
+                        let Rgb(r, g, b) = ramp.at(0.15 + 0.7 * t);
+                        format!(
+                            " [color=\"#{:02x}{:02x}{:02x}\", penwidth={:.1}, label=\"{}\"]",
+                            r,
+                            g,
+                            b,
+                            1.0 + 4.0 * t,
+                            count
+                        )
+                    }
+                    _ => String::new(),
+                };
+
+                writeln!(
+                    result,
+                    "    {} -- {}{};",
+                    quote(self.name(a)),
+                    quote(self.name(b)),
+                    style
+                )
+                .unwrap();
+            }
+        }
+
+        result.push_str("}\n");
+        result
+    }
+}
+
+// Cave names can be any text, so they are always written as quoted IDs
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
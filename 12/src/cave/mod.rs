@@ -0,0 +1,206 @@
+mod dot;
+mod paths;
+mod policy;
+
+pub use dot::Overlay;
+use paths::PathError;
+pub use policy::{CavePolicy, VisitPolicy};
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+// Cave system where every cave is interned into an id (its index), and the
+// tunnels are stored as adjacency lists
+#[derive(Debug, Clone)]
+pub struct CaveGraph {
+    names: Vec<String>,
+    small: Vec<bool>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl CaveGraph {
+    pub fn init<'a, I>(tunnels: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let mut result = Self {
+            names: Vec::new(),
+            small: Vec::new(),
+            adjacency: Vec::new(),
+        };
+
+        for (a, b) in tunnels {
+            let (a, b) = (result.intern(a), result.intern(b));
+            result.adjacency[a].push(b);
+            result.adjacency[b].push(a);
+        }
+
+        result
+    }
+
+    pub fn parse(filepath: &str) -> Self {
+        let file = File::open(filepath).expect("Error while opening file");
+        let reader = BufReader::new(file);
+
+        let lines: Vec<String> = reader.lines().map(|l| l.unwrap()).collect();
+        Self::init(lines.iter().map(|l| {
+            l.split_once('-')
+                .expect("Tunnel is not in the 'a-b' format")
+        }))
+    }
+
+    fn intern(&mut self, name: &str) -> usize {
+        if let Some(id) = self.id(name) {
+            return id;
+        }
+
+        self.names.push(name.to_owned());
+        self.small.push(name.to_lowercase() == name);
+        self.adjacency.push(Vec::new());
+        self.names.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    pub fn is_small(&self, id: usize) -> bool {
+        self.small[id]
+    }
+
+    // "start" and "end" can only be visited once
+    pub fn is_terminal(&self, id: usize) -> bool {
+        self.names[id] == "start" || self.names[id] == "end"
+    }
+
+    pub fn neighbours(&self, id: usize) -> &[usize] {
+        &self.adjacency[id]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    const EXAMPLE: [(&str, &str); 10] = [
+        ("dc", "end"),
+        ("HN", "start"),
+        ("start", "kj"),
+        ("dc", "start"),
+        ("dc", "HN"),
+        ("LN", "dc"),
+        ("HN", "end"),
+        ("kj", "sa"),
+        ("kj", "HN"),
+        ("kj", "dc"),
+    ];
+
+    #[test]
+    fn test_count_paths() {
+        let graph = CaveGraph::init(EXAMPLE);
+        let policy = CavePolicy::new();
+
+        assert_eq!(graph.count_paths(&policy), Ok(19));
+        assert_eq!(
+            graph.count_paths(&policy.clone().with_revisits(1)),
+            Ok(103)
+        );
+    }
+
+    #[test]
+    fn test_paths_match_count() {
+        let graph = CaveGraph::init(EXAMPLE);
+        let kj = graph.id("kj").unwrap();
+        let policies = [
+            CavePolicy::new().with_revisits(2),
+            CavePolicy::new().with_limit(kj, Some(3)),
+            CavePolicy::new().with_forbidden(graph.id("HN").unwrap()),
+            CavePolicy::new()
+                .with_revisits(1)
+                .with_waypoint(kj)
+                .with_waypoint(graph.id("HN").unwrap()),
+        ];
+
+        for policy in policies {
+            let paths = graph.paths(&policy).unwrap().collect::<Vec<_>>();
+            assert_eq!(Ok(paths.len() as u64), graph.count_paths(&policy));
+            assert_eq!(paths.iter().unique().count(), paths.len());
+            assert!(policy
+                .waypoints()
+                .iter()
+                .all(|w| paths.iter().all(|p| p.contains(w))));
+        }
+    }
+
+    #[test]
+    fn test_traffic() {
+        let graph = CaveGraph::init(EXAMPLE);
+        let traffic = graph.traffic(&CavePolicy::new()).unwrap();
+        let tunnel = |a, b| {
+            CaveGraph::tunnel(graph.id(a).unwrap(), graph.id(b).unwrap())
+        };
+
+        // Every path leaves "start" through one of its three tunnels
+        assert_eq!(
+            ["HN", "kj", "dc"]
+                .iter()
+                .map(|c| traffic[&tunnel("start", c)])
+                .sum::<u64>(),
+            19
+        );
+        assert!(graph.to_dot(&Overlay::Traffic(&traffic)).contains("label="));
+    }
+
+    #[test]
+    fn test_dot() {
+        let graph = CaveGraph::init([("start", "a\"b"), ("a\"b", "end")]);
+        let ids = |names: &[&str]| {
+            names
+                .iter()
+                .map(|n| graph.id(n).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let dot = graph.to_dot(&Overlay::Path(&ids(&["start", "a\"b", "end"])));
+        assert!(dot.contains("    \"a\\\"b\" [shape=circle];\n"));
+        assert!(dot.contains("    \"start\" -- \"a\\\"b\" [color="));
+
+        assert_eq!(graph.missing_tunnel(&ids(&["start", "a\"b", "end"])), None);
+        assert_eq!(
+            graph.missing_tunnel(&ids(&["start", "end"])),
+            Some((graph.id("start").unwrap(), graph.id("end").unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_infinite_paths() {
+        let graph = CaveGraph::init([("start", "A"), ("A", "B"), ("B", "end")]);
+
+        assert!(matches!(
+            graph.count_paths(&CavePolicy::new()),
+            Err(PathError::Infinite(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_visit_counts() {
+        let graph = CaveGraph::init(EXAMPLE);
+        let kj = graph.id("kj").unwrap();
+
+        // "start", "end" and 3 small caves, with counts up to 33 bits each
+        let policy = CavePolicy::new().with_limit(kj, Some(u32::MAX));
+        assert_eq!(
+            graph.count_paths(&policy.with_revisits(u32::MAX)),
+            Err(PathError::TooManyVisits(5 * 33))
+        );
+    }
+}
@@ -0,0 +1,299 @@
+use super::{CaveGraph, VisitPolicy};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathError {
+    // Cave system has no "start" or no "end"
+    MissingCave(&'static str),
+    // Two connected caves without a visit limit allow infinitely many paths
+    Infinite(String, String),
+    // Visit counts take more bits than a memoization key holds
+    TooManyVisits(u32),
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingCave(name) => write!(f, "there is no '{}' cave", name),
+            Self::Infinite(a, b) => write!(
+                f,
+                "caves '{}' and '{}' can be walked back and forth forever",
+                a, b
+            ),
+            Self::TooManyVisits(bits) => write!(
+                f,
+                "visit counts need {} bits, more than the {} available",
+                bits,
+                u128::BITS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+// State shared by the path counter and the path iterator: how many times each
+// cave was visited, and how many revisits were used. Caves without a limit are
+// not counted (except waypoints, which only need to be visited once). Counts
+// are packed into bit fields, so that the state is a small memoization key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Visits {
+    counts: u128,
+    revisits: u32,
+}
+
+// What entering a cave changed, so it can be undone when leaving it
+#[derive(Debug, Clone, Copy, Default)]
+struct Entry {
+    previous: u32,
+    revisit: bool,
+}
+
+// Bit field of a cave visit count: shift and width
+type Field = (u32, u32);
+
+struct Walker<'a, P> {
+    graph: &'a CaveGraph,
+    policy: &'a P,
+    limits: Vec<Option<u32>>,
+    fields: Vec<Option<Field>>,
+    start: usize,
+    end: usize,
+}
+
+impl<'a, P: VisitPolicy> Walker<'a, P> {
+    fn init(graph: &'a CaveGraph, policy: &'a P) -> Result<Self, PathError> {
+        let start = graph.id("start").ok_or(PathError::MissingCave("start"))?;
+        let end = graph.id("end").ok_or(PathError::MissingCave("end"))?;
+        let limits = (0..graph.len())
+            .map(|c| policy.limit(graph, c))
+            .collect::<Vec<_>>();
+
+        let is_open = |c: usize| !policy.is_forbidden(graph, c);
+        for cave in (0..graph.len()).filter(|&c| is_open(c)) {
+            for &next in graph.neighbours(cave) {
+                if limits[cave].is_none()
+                    && limits[next].is_none()
+                    && is_open(next)
+                {
+                    return Err(PathError::Infinite(
+                        graph.name(cave).to_owned(),
+                        graph.name(next).to_owned(),
+                    ));
+                }
+            }
+        }
+
+        // Counts go up to the limit plus every revisit
+        let mut fields = vec![None; graph.len()];
+        let mut bits = 0;
+        for cave in 0..graph.len() {
+            let max = match limits[cave] {
+                Some(limit) => limit as u64 + policy.revisits() as u64,
+                None if policy.waypoints().contains(&cave) => 1,
+                None => continue,
+            };
+            let width = u64::BITS - max.leading_zeros();
+            fields[cave] = Some((bits, width));
+            bits += width;
+        }
+        if bits > u128::BITS {
+            return Err(PathError::TooManyVisits(bits));
+        }
+
+        Ok(Self {
+            graph,
+            policy,
+            limits,
+            fields,
+            start,
+            end,
+        })
+    }
+
+    fn origin(&self) -> Visits {
+        let mut result = Visits {
+            counts: 0,
+            revisits: 0,
+        };
+        self.enter(&mut result, self.start);
+
+        result
+    }
+
+    fn visits(&self, visits: &Visits, cave: usize) -> u32 {
+        match self.fields[cave] {
+            Some((shift, width)) => {
+                ((visits.counts >> shift) & ((1 << width) - 1)) as u32
+            }
+            None => 0,
+        }
+    }
+
+    fn set_visits(&self, visits: &mut Visits, cave: usize, count: u32) {
+        if let Some((shift, width)) = self.fields[cave] {
+            let mask = ((1u128 << width) - 1) << shift;
+            visits.counts = visits.counts & !mask | (count as u128) << shift;
+        }
+    }
+
+    // Outputs whether entering `cave` uses a revisit, or None if it cannot
+    // be entered at all
+    fn can_enter(&self, visits: &Visits, cave: usize) -> Option<bool> {
+        if self.policy.is_forbidden(self.graph, cave) {
+            return None;
+        }
+
+        match self.limits[cave] {
+            None => Some(false),
+            Some(limit) if self.visits(visits, cave) < limit => Some(false),
+            Some(_)
+                if visits.revisits < self.policy.revisits()
+                    && self.policy.can_revisit(self.graph, cave) =>
+            {
+                Some(true)
+            }
+            _ => None,
+        }
+    }
+
+    fn enter(&self, visits: &mut Visits, cave: usize) -> Entry {
+        let entry = Entry {
+            previous: self.visits(visits, cave),
+            revisit: self.can_enter(visits, cave) == Some(true),
+        };
+
+        // Waypoints without a limit only record that they were visited
+        let count = match self.limits[cave] {
+            Some(_) => entry.previous + 1,
+            None => 1,
+        };
+        self.set_visits(visits, cave, count);
+        visits.revisits += entry.revisit as u32;
+
+        entry
+    }
+
+    fn leave(&self, visits: &mut Visits, cave: usize, entry: Entry) {
+        self.set_visits(visits, cave, entry.previous);
+        visits.revisits -= entry.revisit as u32;
+    }
+
+    fn is_complete(&self, visits: &Visits) -> bool {
+        self.policy
+            .waypoints()
+            .iter()
+            .all(|w| self.visits(visits, *w) > 0)
+    }
+
+    // Memoized on (cave, visits). Waypoints make the state of unlimited caves
+    // matter, which is why they are tracked too
+    fn count(
+        &self,
+        cave: usize,
+        visits: &mut Visits,
+        memo: &mut HashMap<(usize, Visits), u64>,
+    ) -> u64 {
+        if cave == self.end {
+            return self.is_complete(visits) as u64;
+        }
+
+        if let Some(count) = memo.get(&(cave, *visits)) {
+            return *count;
+        }
+
+        let mut result = 0;
+        for &next in self.graph.neighbours(cave) {
+            if self.can_enter(visits, next).is_none() {
+                continue;
+            }
+
+            let entry = self.enter(visits, next);
+            result += self.count(next, visits, memo);
+            self.leave(visits, next, entry);
+        }
+
+        memo.insert((cave, *visits), result);
+        result
+    }
+}
+
+impl CaveGraph {
+    // Number of paths from "start" to "end" allowed by the policy
+    pub fn count_paths<P: VisitPolicy>(
+        &self,
+        policy: &P,
+    ) -> Result<u64, PathError> {
+        let walker = Walker::init(self, policy)?;
+        let mut visits = walker.origin();
+
+        Ok(walker.count(walker.start, &mut visits, &mut HashMap::new()))
+    }
+
+    // Lazily enumerates the paths from "start" to "end" allowed by the policy
+    pub fn paths<'a, P: VisitPolicy>(
+        &'a self,
+        policy: &'a P,
+    ) -> Result<Paths<'a, P>, PathError> {
+        let walker = Walker::init(self, policy)?;
+        let visits = walker.origin();
+
+        Ok(Paths {
+            stack: vec![(walker.start, 0, Entry::default())],
+            walker,
+            visits,
+        })
+    }
+}
+
+// Depth first search with an explicit stack, yielding one path (as cave ids)
+// at a time
+pub struct Paths<'a, P> {
+    walker: Walker<'a, P>,
+    visits: Visits,
+    // Cave, index of the next neighbour to try, and how it was entered
+    stack: Vec<(usize, usize, Entry)>,
+}
+
+impl<P: VisitPolicy> Iterator for Paths<'_, P> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let walker = &self.walker;
+
+        while let Some((cave, index, entry)) = self.stack.last_mut() {
+            let (cave, entry) = (*cave, *entry);
+            let next = walker.graph.neighbours(cave).get(*index).copied();
+            *index += 1;
+
+            match next {
+                // All neighbours explored, so backtrack
+                None => {
+                    self.stack.pop();
+                    if !self.stack.is_empty() {
+                        walker.leave(&mut self.visits, cave, entry);
+                    }
+                }
+                Some(next)
+                    if walker.can_enter(&self.visits, next).is_some() =>
+                {
+                    if next == walker.end {
+                        if walker.is_complete(&self.visits) {
+                            let mut path: Vec<usize> =
+                                self.stack.iter().map(|(c, _, _)| *c).collect();
+                            path.push(next);
+                            return Some(path);
+                        }
+                    } else {
+                        let entry = walker.enter(&mut self.visits, next);
+                        self.stack.push((next, 0, entry));
+                    }
+                }
+                Some(_) => (),
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,93 @@
+use super::CaveGraph;
+use std::collections::HashMap;
+
+// Decides which caves a path may enter, and how often
+pub trait VisitPolicy {
+    // Number of times a cave can be visited (None for unlimited)
+    fn limit(&self, graph: &CaveGraph, cave: usize) -> Option<u32>;
+
+    // Number of visits above the limit of a cave, shared by the whole path
+    fn revisits(&self) -> u32 {
+        0
+    }
+
+    // Whether a cave that reached its limit can use one of the revisits
+    fn can_revisit(&self, _graph: &CaveGraph, _cave: usize) -> bool {
+        true
+    }
+
+    fn is_forbidden(&self, _graph: &CaveGraph, _cave: usize) -> bool {
+        false
+    }
+
+    // Caves that every path has to visit before reaching "end"
+    fn waypoints(&self) -> &[usize] {
+        &[]
+    }
+}
+
+// Policy of the submarine: small caves once, big caves any number of times,
+// and "start"/"end" exactly once. Every rule can be tuned on top of that
+#[derive(Debug, Clone, Default)]
+pub struct CavePolicy {
+    limits: HashMap<usize, Option<u32>>,
+    revisits: u32,
+    forbidden: Vec<usize>,
+    waypoints: Vec<usize>,
+}
+
+impl CavePolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_revisits(mut self, revisits: u32) -> Self {
+        self.revisits = revisits;
+        self
+    }
+
+    pub fn with_limit(mut self, cave: usize, limit: Option<u32>) -> Self {
+        self.limits.insert(cave, limit);
+        self
+    }
+
+    pub fn with_forbidden(mut self, cave: usize) -> Self {
+        self.forbidden.push(cave);
+        self
+    }
+
+    pub fn with_waypoint(mut self, cave: usize) -> Self {
+        self.waypoints.push(cave);
+        self
+    }
+}
+
+impl VisitPolicy for CavePolicy {
+    fn limit(&self, graph: &CaveGraph, cave: usize) -> Option<u32> {
+        if graph.is_terminal(cave) {
+            Some(1)
+        } else if let Some(limit) = self.limits.get(&cave) {
+            *limit
+        } else if graph.is_small(cave) {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    fn revisits(&self) -> u32 {
+        self.revisits
+    }
+
+    fn can_revisit(&self, graph: &CaveGraph, cave: usize) -> bool {
+        !graph.is_terminal(cave)
+    }
+
+    fn is_forbidden(&self, _graph: &CaveGraph, cave: usize) -> bool {
+        self.forbidden.contains(&cave)
+    }
+
+    fn waypoints(&self) -> &[usize] {
+        &self.waypoints
+    }
+}
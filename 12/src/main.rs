@@ -1,107 +1,54 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+mod cave;
 
-use itertools::Itertools;
+use cave::{CaveGraph, CavePolicy, Overlay};
 
-#[derive(Debug, Clone)]
-struct SubmarinePath {
-    twice: Option<String>,
-    path: Vec<String>,
-    tunnels: Vec<(String, String)>,
+// Caves listed (comma separated) after an option, as ids
+fn caves_option(graph: &CaveGraph, name: &str) -> Vec<usize> {
+    common::args::option(name)
+        .map(|v| {
+            v.split(',')
+                .map(|c| graph.id(c).expect("Unknown cave"))
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
-impl SubmarinePath {
-    fn init(tunnels: Vec<(String, String)>) -> Self {
-        Self {
-            twice: None,
-            path: vec!["start".to_owned()],
-            tunnels,
-        }
-    }
-
-    fn revisit(&self) -> Vec<Self> {
-        self.tunnels
-            .iter()
-            // Join both left and right sides of the tunnel tuple into a single
-            // interator
-            .map(|(cl, _)| cl)
-            .chain(self.tunnels.iter().map(|(_, cr)| cr))
-            // Filter by caves that you can only visit once
-            .filter(|c| c.to_lowercase().eq(*c) && *c != "start" && *c != "end")
-            .unique()
-            // Create vector of elements containing a copy of itself,
-            // except a cave will be able to be revisited twice
-            .map(|c| Self {
-                twice: Some(c.clone()),
-                path: self.path.clone(),
-                tunnels: self.tunnels.clone(),
-            })
-            .collect()
+// Custom policy from the command line:
+// `--revisits <k>`, `--limit <cave>=<n|*>,...`, `--forbid <cave>,...` and
+// `--via <cave>,...`
+fn policy_from_args(graph: &CaveGraph) -> Option<CavePolicy> {
+    let options = ["--revisits", "--limit", "--forbid", "--via"];
+    if !options.iter().any(|o| common::args::flag(o)) {
+        return None;
     }
 
-    fn append(&mut self, next: &str) {
-        // Search is over when we receive an end
-        if next.eq("end") {
-            self.tunnels.clear()
-        // Remove all tunnels containing curr and next if cave is lowercase
-        } else if let Some(curr) = self.path.last() {
-            if curr.to_lowercase().eq(curr) {
-                // Except when the cave is stored in 'smalltwice'
-                if self.twice.is_some() && self.twice.as_ref().unwrap().eq(curr) {
-                    self.twice = None;
-                } else {
-                    self.tunnels
-                        .retain(|(cl, cr)| !(cl.eq(curr) || cr.eq(curr)));
-                }
-            }
-        }
+    let mut result = CavePolicy::new().with_revisits(
+        common::args::option("--revisits")
+            .map(|v| v.parse().expect("Invalid number of revisits"))
+            .unwrap_or(0),
+    );
 
-        // Update new current cave (last one)
-        self.path.push(next.to_owned());
+    for limit in common::args::option("--limit")
+        .iter()
+        .flat_map(|v| v.split(','))
+    {
+        let (cave, n) = limit.split_once('=').expect("Limit is not 'cave=n'");
+        result = result.with_limit(
+            graph.id(cave).expect("Unknown cave"),
+            match n {
+                "*" => None,
+                n => Some(n.parse().expect("Invalid limit")),
+            },
+        );
     }
-
-    fn is_ended(&self) -> bool {
-        !self.path.is_empty() && self.path.last().unwrap().eq("end")
+    for cave in caves_option(graph, "--forbid") {
+        result = result.with_forbidden(cave);
     }
-
-    fn parse(filepath: &str) -> Self {
-        let file = File::open(filepath).expect("Error while opening file");
-        let reader = BufReader::new(file);
-
-        Self::init(
-            reader
-                .lines()
-                .map(|l| {
-                    l.unwrap()
-                        .split('-')
-                        .map(|c| c.to_owned())
-                        .collect_tuple()
-                        .unwrap()
-                })
-                .collect(),
-        )
+    for cave in caves_option(graph, "--via") {
+        result = result.with_waypoint(cave);
     }
 
-    fn step(&self) -> Vec<Self> {
-        let mut result = Vec::new();
-
-        for next in self.tunnels.iter().filter_map(|(cl, cr)| {
-            let curr = self.path.last().unwrap();
-            if curr.eq(cl) {
-                Some(cr)
-            } else if curr.eq(cr) {
-                Some(cl)
-            } else {
-                None
-            }
-        }) {
-            let mut branch = self.clone();
-            branch.append(next);
-            result.push(branch);
-        }
-
-        result
-    }
+    Some(result)
 }
 
 fn main() {
@@ -109,34 +56,63 @@ fn main() {
         .nth(1)
         .expect("Filepath for input not provided");
 
-    // -------------------------------------------------------------------------
-    // Part 1
-    // -------------------------------------------------------------------------
-    let mut graphs = vec![SubmarinePath::parse(&filepath)];
-    let mut finished = Vec::new();
-    while let Some(g) = graphs.pop() {
-        if g.is_ended() {
-            finished.push(g);
-        } else {
-            graphs.append(&mut g.step());
+    let graph = CaveGraph::parse(&filepath);
+    let policy = CavePolicy::new();
+
+    println!(
+        "Problem #1: {:?}",
+        graph.count_paths(&policy).expect("Unable to count paths")
+    );
+    println!(
+        "Problem #2: {:?}",
+        graph
+            .count_paths(&policy.with_revisits(1))
+            .expect("Unable to count paths")
+    );
+
+    // Graphviz export, optionally with a path (`--dot-path start,...,end`) or
+    // the traffic of the paths (`--dot-traffic`) drawn on top
+    if let Some(output) = common::args::option("--dot") {
+        let path = caves_option(&graph, "--dot-path");
+        if let Some((a, b)) = graph.missing_tunnel(&path) {
+            panic!(
+                "No tunnel between '{}' and '{}'",
+                graph.name(a),
+                graph.name(b)
+            );
         }
+        // Traffic enumerates every path, which can take very long
+        let traffic = common::args::flag("--dot-traffic").then(|| {
+            graph
+                .traffic(&policy_from_args(&graph).unwrap_or_default())
+                .expect("Unable to count paths")
+        });
+
+        let overlay = match (&traffic, path.is_empty()) {
+            (Some(traffic), _) => Overlay::Traffic(traffic),
+            (None, false) => Overlay::Path(&path),
+            (None, true) => Overlay::None,
+        };
+        std::fs::write(output, graph.to_dot(&overlay))
+            .expect("Error while writing graph");
     }
-    println!("Problem #1: {:?}", finished.len());
 
-    // -------------------------------------------------------------------------
-    // Part 2
-    // -------------------------------------------------------------------------
-    let mut graphs = SubmarinePath::parse(&filepath).revisit();
-    let mut finished = Vec::new();
-    while let Some(g) = graphs.pop() {
-        if g.is_ended() {
-            finished.push(g);
-        } else {
-            graphs.append(&mut g.step());
+    if let Some(policy) = policy_from_args(&graph) {
+        match graph.count_paths(&policy) {
+            Ok(count) => println!("Custom policy: {}", count),
+            Err(e) => println!("Custom policy: {}", e),
+        }
+
+        if common::args::flag("--list") {
+            for path in graph.paths(&policy).into_iter().flatten() {
+                println!(
+                    "{}",
+                    path.iter()
+                        .map(|c| graph.name(*c))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                );
+            }
         }
     }
-    println!(
-        "Problem #2: {:?}",
-        finished.iter().unique_by(|g| g.path.clone()).count()
-    );
 }
@@ -1,141 +1,50 @@
-use core::panic;
-use grid::Grid;
-use itertools::Itertools;
-use std::fmt;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+mod ocr;
+mod paper;
 
-#[derive(Debug)]
-enum OrigamiFold {
-    Left(usize),
-    Up(usize),
-}
+use paper::{OrigamiPaper, Side};
+use rand::Rng;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
 
-impl OrigamiFold {
-    fn init(line: &str) -> Self {
-        let values = line
-            .split("fold along ")
-            .last()
-            .unwrap()
-            .split("=")
-            .collect_tuple::<(&str, &str)>()
-            .unwrap();
+fn main() {
+    let filepath = std::env::args()
+        .nth(1)
+        .expect("Filepath for input not provided");
 
-        match values.0 {
-            "x" => Self::Left(values.1.parse().unwrap()),
-            "y" => Self::Up(values.1.parse().unwrap()),
-            _ => panic!("Unexpected fold expression {:?}", values),
-        }
-    }
-}
+    let file = File::open(&filepath).expect("Error while opening file");
+    let mut origami = OrigamiPaper::parse(BufReader::new(file))
+        .expect("Error while reading file");
 
-#[derive(Debug)]
-struct OrigamiPaper {
-    grid: Grid<bool>,
-    rows: usize,
-    cols: usize,
-    folds: Vec<OrigamiFold>,
-}
+    let mut folds = vec![origami.fold().expect("Error while folding")];
+    println!("Problem #1: {}", origami.len());
 
-impl fmt::Display for OrigamiPaper {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for r in 0..self.rows {
-            writeln!(
-                f,
-                "{:02}: {}",
-                r,
-                (0..self.cols)
-                    .map(|c| if self.grid[r][c] { "#" } else { "." })
-                    .join(""),
-            )?
-        }
-        Ok(())
+    while !origami.folds.is_empty() {
+        folds.push(origami.fold().expect("Error while folding"));
     }
-}
-
-impl OrigamiPaper {
-    fn parse(filepath: &str) -> Self {
-        let file = File::open(filepath).expect("Error while opening file");
-        let reader = BufReader::new(file);
-
-        let mut dots = Vec::new();
-        let mut folds = Vec::new();
-        let mut x_max = 0usize;
-        let mut y_max = 0usize;
 
-        for l in reader.lines().map(|l| l.unwrap()) {
-            if !l.is_empty() {
-                if l.starts_with("fold") {
-                    folds.push(OrigamiFold::init(&l));
-                } else {
-                    let (x, y) = l
-                        .split(',')
-                        .map(|s| s.parse().unwrap())
-                        .collect_tuple::<(usize, usize)>()
-                        .unwrap();
-
-                    x_max = std::cmp::max(x_max, x);
-                    y_max = std::cmp::max(y_max, y);
-                    dots.push((x, y));
-                }
-            }
-        }
-
-        let mut grid = Grid::init(y_max + 1, x_max + 1, false);
-        dots.into_iter().for_each(|(x, y)| grid[y][x] = true);
-
-        Self {
-            rows: grid.rows(),
-            cols: grid.cols(),
-            grid: grid,
-            folds: folds.into_iter().rev().collect(),
+    // Falls back to showing the paper when the letters cannot be read
+    match ocr::read(&origami.dots) {
+        Ok(code) => println!("Problem #2: {}", code),
+        Err(e) => {
+            println!("Problem #2: {}", e);
+            println!("{}", origami);
         }
     }
 
-    fn len(&self) -> usize {
-        (0..self.rows)
-            .cartesian_product(0..self.cols)
-            .filter(|(r, c)| self.grid[*r][*c])
-            .count()
-    }
-
-    fn fold(&mut self) {
-        match self.folds.pop().unwrap() {
-            OrigamiFold::Left(value) => {
-                let range = std::cmp::min(value, self.cols - value - 1);
-                self.cols = value;
-                for r in 0..self.rows {
-                    for c in 1..=range {
-                        self.grid[r][value - c] |= self.grid[r][value + c];
-                    }
-                }
-            }
-            OrigamiFold::Up(value) => {
-                let range = std::cmp::min(value, self.rows - value - 1);
-                self.rows = value;
-                for r in 1..=range {
-                    for c in 0..self.cols {
-                        self.grid[value - r][c] |= self.grid[value + r][c];
-                    }
-                }
-            }
+    // A new puzzle with the same folds and code, unfolding each dot randomly
+    if let Some(output) = common::args::option("--generate") {
+        let mut rng = rand::thread_rng();
+        for fold in folds.into_iter().rev() {
+            origami.unfold(fold, |_| match rng.gen_range(0..3) {
+                0 => Side::Kept,
+                1 => Side::Folded,
+                _ => Side::Both,
+            });
         }
-    }
-}
-
-fn main() {
-    let filepath = std::env::args()
-        .nth(1)
-        .expect("Filepath for input not provided");
-
-    let mut origami = OrigamiPaper::parse(&filepath);
-
-    origami.fold();
-    println!("Problem #1: {}", origami.len());
 
-    while origami.folds.len() > 0 {
-        origami.fold();
+        let file = File::create(output).expect("Error while creating file");
+        origami
+            .write(BufWriter::new(file))
+            .expect("Error while writing puzzle");
     }
-    println!("Problem #2:");
-    println!("{}", origami);
 }
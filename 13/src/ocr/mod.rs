@@ -0,0 +1,170 @@
+use std::collections::HashSet;
+use std::fmt;
+
+// Letters of the 4x6 font (plus one blank column of spacing) used to display
+// codes, with '#' for lit pixels
+const FONT: [(char, [&str; 6]); 17] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+const WIDTH: usize = 4;
+const HEIGHT: usize = 6;
+const SPACING: usize = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    // Dots do not span the height of a letter
+    Height(usize),
+    // Letter at this index is not part of the font
+    UnknownLetter(usize),
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Height(h) => {
+                write!(f, "text is {} dots high instead of {}", h, HEIGHT)
+            }
+            Self::UnknownLetter(i) => write!(f, "letter #{} is unknown", i + 1),
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+// Each letter is encoded as a bitmask, one bit per pixel in row major order
+fn encode<F>(is_lit: F) -> u32
+where
+    F: Fn(usize, usize) -> bool,
+{
+    (0..HEIGHT)
+        .flat_map(|y| (0..WIDTH).map(move |x| (x, y)))
+        .enumerate()
+        .filter(|(_, (x, y))| is_lit(*x, *y))
+        .fold(0, |acc, (i, _)| acc | (1 << i))
+}
+
+// Reads the letters formed by a set of (x, y) dots. The text can be anywhere,
+// so it is aligned with its left-most dot, or a few columns before it when the
+// first letter starts with blank columns (e.g. 'I')
+pub fn read(dots: &HashSet<(usize, usize)>) -> Result<String, OcrError> {
+    if dots.is_empty() {
+        return Ok(String::new());
+    }
+
+    let x_min = dots.iter().map(|(x, _)| *x).min().unwrap();
+    let y_min = dots.iter().map(|(_, y)| *y).min().unwrap();
+    let y_max = dots.iter().map(|(_, y)| *y).max().unwrap();
+
+    if y_max - y_min + 1 != HEIGHT {
+        return Err(OcrError::Height(y_max - y_min + 1));
+    }
+
+    let font = FONT
+        .iter()
+        .map(|(c, rows)| (*c, encode(|x, y| rows[y].as_bytes()[x] == b'#')))
+        .collect::<Vec<_>>();
+
+    // Errors are the ones found when aligned with the left-most dot
+    let read_at = |left| read_from(dots, &font, (left, y_min));
+    let aligned = read_at(x_min);
+    if aligned.is_ok() {
+        return aligned;
+    }
+
+    (1..WIDTH.min(x_min + 1))
+        .map(|shift| read_at(x_min - shift))
+        .find(|r| r.is_ok())
+        .unwrap_or(aligned)
+}
+
+// Letters of a text whose top-left corner is `origin`
+fn read_from(
+    dots: &HashSet<(usize, usize)>,
+    font: &[(char, u32)],
+    (left, top): (usize, usize),
+) -> Result<String, OcrError> {
+    let x_max = dots.iter().map(|(x, _)| *x).max().unwrap();
+    let letters = (x_max - left) / (WIDTH + SPACING) + 1;
+
+    (0..letters)
+        .map(|index| {
+            let left = left + index * (WIDTH + SPACING);
+            let glyph = encode(|x, y| dots.contains(&(left + x, top + y)));
+
+            font.iter()
+                .find(|(_, g)| *g == glyph)
+                .map(|(c, _)| *c)
+                .ok_or(OcrError::UnknownLetter(index))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dots(rows: &[&str]) -> HashSet<(usize, usize)> {
+        rows.iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .filter(|(_, c)| *c == '#')
+                    .map(move |(x, _)| (x, y))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_read() {
+        let text = dots(&[
+            "#..#.###..####",
+            "#..#.#..#.#...",
+            "####.###..###.",
+            "#..#.#..#.#...",
+            "#..#.#..#.#...",
+            "#..#.###..####",
+        ]);
+        assert_eq!(read(&text), Ok("HBE".to_owned()));
+
+        // 'I' starts with a blank column, wherever the text is
+        let rows = [
+            ".###.#..#",
+            "..#..#..#",
+            "..#..####",
+            "..#..#..#",
+            "..#..#..#",
+            ".###.#..#",
+        ];
+        assert_eq!(read(&dots(&rows)), Ok("IH".to_owned()));
+
+        let shifted = dots(&rows).iter().map(|(x, y)| (x + 7, y + 3)).collect();
+        assert_eq!(read(&shifted), Ok("IH".to_owned()));
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert_eq!(read(&dots(&["#", "#"])), Err(OcrError::Height(2)));
+        assert_eq!(
+            read(&dots(&["#..#", "#..#", "####", "#..#", "#..#", "#.##"])),
+            Err(OcrError::UnknownLetter(0))
+        );
+    }
+}
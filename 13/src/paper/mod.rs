@@ -0,0 +1,374 @@
+use itertools::Itertools;
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrigamiFold {
+    Left(usize),
+    Up(usize),
+}
+
+// e.g. "fold along x=5"
+impl FromStr for OrigamiFold {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid fold '{}'", s);
+        let (axis, value) = s
+            .strip_prefix("fold along ")
+            .and_then(|f| f.split_once('='))
+            .ok_or_else(error)?;
+        let value = value.parse().map_err(|_| error())?;
+
+        match axis {
+            "x" => Ok(Self::Left(value)),
+            "y" => Ok(Self::Up(value)),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl fmt::Display for OrigamiFold {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Left(v) => write!(f, "fold along x={}", v),
+            Self::Up(v) => write!(f, "fold along y={}", v),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FoldError {
+    NoFolds,
+    // Dots on the fold line would disappear into the crease
+    DotOnLine {
+        fold: OrigamiFold,
+        dot: (usize, usize),
+    },
+    // The line is not inside the paper, given as (columns, rows)
+    Outside {
+        fold: OrigamiFold,
+        size: (usize, usize),
+    },
+}
+
+impl fmt::Display for FoldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoFolds => write!(f, "no folds left"),
+            Self::DotOnLine { fold, dot } => {
+                write!(f, "{}: dot {},{} is on the line", fold, dot.0, dot.1)
+            }
+            Self::Outside { fold, size } => {
+                write!(
+                    f,
+                    "{}: line outside a {}x{} paper",
+                    fold, size.0, size.1
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for FoldError {}
+
+// Where a dot of the folded paper was before folding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    // On the half that stays in place
+    Kept,
+    // On the half that is folded over
+    Folded,
+    // On both halves, overlapping after the fold
+    Both,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrigamiPaper {
+    pub dots: HashSet<(usize, usize)>,
+    // Pending folds, with the next one last
+    pub folds: Vec<OrigamiFold>,
+    cols: usize,
+    rows: usize,
+}
+
+impl fmt::Display for OrigamiPaper {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for r in 0..self.rows {
+            writeln!(
+                f,
+                "{:02}: {}",
+                r,
+                (0..self.cols)
+                    .map(|c| if self.dots.contains(&(c, r)) {
+                        "#"
+                    } else {
+                        "."
+                    })
+                    .join(""),
+            )?
+        }
+        Ok(())
+    }
+}
+
+impl OrigamiPaper {
+    // The paper is large enough for all dots, and (as the first fold of each
+    // direction is expected to be in the middle) for both halves of those
+    pub fn new(dots: HashSet<(usize, usize)>, folds: &[OrigamiFold]) -> Self {
+        let first = |f: fn(&OrigamiFold) -> Option<usize>| {
+            folds.iter().find_map(f).map_or(0, |v| 2 * v + 1)
+        };
+        let cols = dots.iter().map(|(x, _)| x + 1).max().unwrap_or(0);
+        let rows = dots.iter().map(|(_, y)| y + 1).max().unwrap_or(0);
+
+        Self {
+            cols: cols.max(first(|f| match f {
+                OrigamiFold::Left(v) => Some(*v),
+                _ => None,
+            })),
+            rows: rows.max(first(|f| match f {
+                OrigamiFold::Up(v) => Some(*v),
+                _ => None,
+            })),
+            dots,
+            folds: folds.iter().rev().copied().collect(),
+        }
+    }
+
+    // Malformed lines are reported as invalid data
+    pub fn parse<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut dots = HashSet::new();
+        let mut folds = Vec::new();
+        let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+
+        for l in reader.lines() {
+            let l = l?;
+            if l.is_empty() {
+                continue;
+            }
+
+            if l.starts_with("fold") {
+                folds.push(l.parse().map_err(invalid)?);
+            } else {
+                let dot = l
+                    .split_once(',')
+                    .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)))
+                    .ok_or_else(|| invalid(format!("invalid dot '{}'", l)))?;
+                dots.insert(dot);
+            }
+        }
+
+        Ok(Self::new(dots, &folds))
+    }
+
+    // Same format as the input, so the paper can be parsed back
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for (x, y) in self.dots.iter().sorted_by_key(|(x, y)| (y, x)) {
+            writeln!(writer, "{},{}", x, y)?;
+        }
+        writeln!(writer)?;
+        for fold in self.folds.iter().rev() {
+            writeln!(writer, "{}", fold)?;
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.dots.len()
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.cols, self.rows)
+    }
+
+    // Only the dots are moved, so folding is linear on the number of dots
+    // (overlapping dots merge into one). When the folded half is the largest,
+    // it becomes the new edge of the paper, shifting the kept half. The paper
+    // is not changed on errors
+    pub fn fold(&mut self) -> Result<OrigamiFold, FoldError> {
+        let fold = *self.folds.last().ok_or(FoldError::NoFolds)?;
+        let (line, width) = match fold {
+            OrigamiFold::Left(v) => (v, self.cols),
+            OrigamiFold::Up(v) => (v, self.rows),
+        };
+
+        if line >= width {
+            return Err(FoldError::Outside {
+                fold,
+                size: self.size(),
+            });
+        }
+
+        let along = |&(x, y): &(usize, usize)| match fold {
+            OrigamiFold::Left(_) => x,
+            OrigamiFold::Up(_) => y,
+        };
+        if let Some(dot) = self.dots.iter().find(|d| along(d) == line) {
+            return Err(FoldError::DotOnLine { fold, dot: *dot });
+        }
+
+        let offset = (width - 1 - line).saturating_sub(line);
+        let mirror = |v: usize| {
+            if v > line {
+                2 * line + offset - v
+            } else {
+                v + offset
+            }
+        };
+
+        self.folds.pop();
+        self.dots = self
+            .dots
+            .iter()
+            .map(|&(x, y)| match fold {
+                OrigamiFold::Left(_) => (mirror(x), y),
+                OrigamiFold::Up(_) => (x, mirror(y)),
+            })
+            .collect();
+
+        let width = line.max(width - 1 - line);
+        match fold {
+            OrigamiFold::Left(_) => self.cols = width,
+            OrigamiFold::Up(_) => self.rows = width,
+        }
+
+        Ok(fold)
+    }
+
+    // Position of a dot before `fold`, on each half of the paper. Dots that
+    // are beyond the line once unfolded can only come from the folded half
+    fn preimage(
+        &self,
+        fold: OrigamiFold,
+        dot: (usize, usize),
+    ) -> (Option<(usize, usize)>, (usize, usize)) {
+        let (line, width, v) = match fold {
+            OrigamiFold::Left(line) => (line, self.cols, dot.0),
+            OrigamiFold::Up(line) => (line, self.rows, dot.1),
+        };
+
+        let offset = width.saturating_sub(line);
+        let kept = v.checked_sub(offset);
+        let folded = 2 * line + offset - v;
+
+        match fold {
+            OrigamiFold::Left(_) => (kept.map(|x| (x, dot.1)), (folded, dot.1)),
+            OrigamiFold::Up(_) => (kept.map(|y| (dot.0, y)), (dot.0, folded)),
+        }
+    }
+
+    // Undoes `fold`, which becomes the next pending fold. The paper is
+    // assumed to have been folded in the middle, unless it is wider than the
+    // line, and `side` chooses where each dot was (dots that could only be on
+    // the folded half are always placed there)
+    pub fn unfold<F>(&mut self, fold: OrigamiFold, mut side: F)
+    where
+        F: FnMut((usize, usize)) -> Side,
+    {
+        let mut dots = HashSet::with_capacity(self.dots.len());
+        for &dot in &self.dots {
+            let (kept, folded) = self.preimage(fold, dot);
+            match (kept, side(dot)) {
+                (Some(k), Side::Kept) => {
+                    dots.insert(k);
+                }
+                (Some(k), Side::Both) => {
+                    dots.insert(k);
+                    dots.insert(folded);
+                }
+                _ => {
+                    dots.insert(folded);
+                }
+            }
+        }
+
+        match fold {
+            OrigamiFold::Left(v) => self.cols = self.cols.max(v) + v + 1,
+            OrigamiFold::Up(v) => self.rows = self.rows.max(v) + v + 1,
+        }
+        self.dots = dots;
+        self.folds.push(fold);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paper(dots: &[(usize, usize)], folds: &[OrigamiFold]) -> OrigamiPaper {
+        OrigamiPaper::new(dots.iter().copied().collect(), folds)
+    }
+
+    #[test]
+    fn test_fold_validation() {
+        let mut p = paper(&[(0, 0), (2, 1)], &[OrigamiFold::Left(2)]);
+        assert_eq!(
+            p.fold(),
+            Err(FoldError::DotOnLine {
+                fold: OrigamiFold::Left(2),
+                dot: (2, 1)
+            })
+        );
+        assert_eq!(p.len(), 2);
+
+        let mut p = paper(&[(0, 1)], &[OrigamiFold::Up(0), OrigamiFold::Up(3)]);
+        assert_eq!(p.fold(), Ok(OrigamiFold::Up(0)));
+        assert!(matches!(p.fold(), Err(FoldError::Outside { .. })));
+        assert_eq!(p.folds, vec![OrigamiFold::Up(3)]);
+    }
+
+    #[test]
+    fn test_fold_asymmetric() {
+        // Folding x=1 on a 6 columns wide paper leaves 4 columns, with the
+        // right-most dot at the new left edge, and the dots at 0 and 2 merged
+        let mut p = paper(&[(0, 0), (2, 0), (5, 0)], &[]);
+        p.folds.push(OrigamiFold::Left(1));
+        p.fold().unwrap();
+
+        assert_eq!(p.size(), (4, 1));
+        assert_eq!(p.dots, HashSet::from([(0, 0), (3, 0)]));
+    }
+
+    #[test]
+    fn test_unfold() {
+        let folded = paper(&[(0, 0), (1, 2)], &[]);
+
+        let mut p = folded.clone();
+        p.unfold(OrigamiFold::Up(3), |d| match d {
+            (0, 0) => Side::Both,
+            _ => Side::Folded,
+        });
+        assert_eq!(p.dots, HashSet::from([(0, 0), (0, 6), (1, 4)]));
+        assert_eq!(p.size(), (2, 7));
+
+        p.fold().unwrap();
+        assert_eq!(p, folded);
+
+        // Every choice folds back into the same paper
+        for side in [Side::Kept, Side::Folded, Side::Both] {
+            let mut p = folded.clone();
+            p.unfold(OrigamiFold::Left(1), |_| side);
+            p.fold().unwrap();
+            assert_eq!(p, folded);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let input = "6,10\n0,14\n\nfold along y=7\nfold along x=5\n";
+        let p = OrigamiPaper::parse(input.as_bytes()).unwrap();
+        assert_eq!(p.folds, vec![OrigamiFold::Left(5), OrigamiFold::Up(7)]);
+
+        let mut output = Vec::new();
+        p.write(&mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), input);
+
+        for input in ["6,x", "6", "fold along z=1", "fold along y=-1"] {
+            let error = OrigamiPaper::parse(input.as_bytes()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+mod polymer;
+
+use itertools::Itertools;
+use num::BigUint;
+use polymer::{Count, Mode, Polymer, PolymerError};
+use std::fmt::Display;
+use std::fs::File;
+use std::io::BufReader;
+
+// Difference between the most and least common elements
+fn spread<T: Count + Display>(
+    poly: &Polymer,
+    steps: usize,
+    mode: Mode,
+    verbose: bool,
+) -> Result<T, PolymerError> {
+    let elements = poly.elements::<T>(steps, mode)?;
+    if verbose {
+        for (i, c) in elements.iter().enumerate() {
+            println!("Element {}: {}", poly.alphabet.element(i), c);
+        }
+    }
+
+    let present = elements
+        .into_iter()
+        .filter(|v| !v.is_zero())
+        .collect::<Vec<_>>();
+    match (present.iter().max(), present.iter().min()) {
+        (Some(max), Some(min)) => Ok(max.clone() - min.clone()),
+        _ => Ok(T::zero()),
+    }
+}
+
+fn run<T: Count + Display>(
+    poly: &Polymer,
+    rounds: Option<usize>,
+    mode: Option<Mode>,
+) {
+    let mode = |steps| mode.unwrap_or_else(|| Mode::auto(poly, steps));
+    let show = |result: Result<T, PolymerError>| match result {
+        Ok(v) => v.to_string(),
+        Err(e) => e.to_string(),
+    };
+
+    match rounds {
+        Some(r) => println!("Answer: {}", show(spread(poly, r, mode(r), true))),
+        None => {
+            println!("Problem #1: {}", show(spread(poly, 10, mode(10), false)));
+            println!("Problem #2: {}", show(spread(poly, 40, mode(40), false)));
+        }
+    }
+}
+
+fn main() {
+    // Parse map filepath from first argument
+    let filepath = std::env::args()
+        .nth(1)
+        .expect("Filepath for polymer not provided");
+
+    // Parse the number of rounds from the second argument (optional, both
+    // problems are solved otherwise)
+    let rounds = std::env::args()
+        .nth(2)
+        .filter(|a| !a.starts_with("--"))
+        .map(|r| r.parse::<usize>().expect("Invalid number of rounds"));
+
+    let file = File::open(&filepath).expect("Error while opening polymer file");
+    let poly = Polymer::parse(BufReader::new(file))
+        .expect("Error while reading polymer file");
+
+    // The polymer is only built for a few rounds, unless a mode is given
+    let mode = match common::args::option("--mode").as_deref() {
+        None => None,
+        Some("explicit") => Some(Mode::Explicit),
+        Some("pairs") => Some(Mode::Pairs),
+        Some(m) => panic!("Unknown mode {}", m),
+    };
+
+    if common::args::flag("--analyse") {
+        let pairs = |pairs: &[[char; 2]]| {
+            pairs.iter().map(|p| p.iter().collect::<String>()).join(",")
+        };
+        let analysis = poly.analyse();
+        println!("Missing rules: {}", pairs(&analysis.missing_rules));
+        println!("Unreachable pairs: {}", pairs(&analysis.unreachable));
+        println!("Absent elements: {}", analysis.absent.iter().join(","));
+    }
+
+    // One line per step, with the count of each element
+    if common::args::flag("--trajectory") {
+        let steps = rounds.unwrap_or(10);
+        match poly.trajectories::<BigUint>(steps) {
+            Ok(t) => {
+                println!("step,{}", poly.alphabet.elements().iter().join(","));
+                for (step, counts) in t.iter().enumerate() {
+                    println!("{},{}", step, counts.iter().join(","));
+                }
+            }
+            Err(e) => println!("Trajectory: {}", e),
+        }
+    }
+
+    // Counts grow exponentially, so larger types are needed for more rounds
+    match common::args::option("--counts").as_deref() {
+        None | Some("u64") => run::<u64>(&poly, rounds, mode),
+        Some("u128") => run::<u128>(&poly, rounds, mode),
+        Some("big") => run::<BigUint>(&poly, rounds, mode),
+        Some(c) => panic!("Unknown count type {}", c),
+    }
+}
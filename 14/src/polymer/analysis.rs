@@ -0,0 +1,104 @@
+use super::{reachable, Count, Polymer, PolymerError};
+
+// What the rules allow to happen to a polymer, from its template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    // Pairs of elements that will never be part of the polymer
+    pub unreachable: Vec<[char; 2]>,
+    // Pairs that can appear, but have no rule (so they never change)
+    pub missing_rules: Vec<[char; 2]>,
+    // Elements that are only mentioned by rules that never apply
+    pub absent: Vec<char>,
+}
+
+impl Polymer {
+    // Pairs that can be part of the polymer after any number of steps
+    fn reachable(&self) -> Vec<bool> {
+        reachable(
+            &self.transitions(),
+            self.template
+                .windows(2)
+                .map(|w| self.alphabet.pair(w[0], w[1]).unwrap()),
+        )
+    }
+
+    pub fn analyse(&self) -> Analysis {
+        let n = self.alphabet.len();
+        let reachable = self.reachable();
+        let pair = |p: usize| {
+            [self.alphabet.element(p / n), self.alphabet.element(p % n)]
+        };
+
+        let mut present = vec![false; n];
+        if let Some(last) = self.template.last() {
+            present[self.alphabet.index(*last).unwrap()] = true;
+        }
+        for p in (0..n * n).filter(|p| reachable[*p]) {
+            present[p / n] = true;
+            present[p % n] = true;
+        }
+
+        Analysis {
+            unreachable: (0..n * n)
+                .filter(|p| !reachable[*p])
+                .map(pair)
+                .collect(),
+            missing_rules: (0..n * n)
+                .filter(|p| reachable[*p])
+                .map(pair)
+                .filter(|p| !self.rules.contains_key(p))
+                .collect(),
+            absent: (0..n)
+                .filter(|e| !present[*e])
+                .map(|e| self.alphabet.element(e))
+                .collect(),
+        }
+    }
+
+    // Counts of each element (following the alphabet order) after each step,
+    // from the template up to `steps`
+    pub fn trajectories<T: Count>(
+        &self,
+        steps: usize,
+    ) -> Result<Vec<Vec<T>>, PolymerError> {
+        let transitions = self.transitions();
+        let mut counts = self.counts::<T>();
+        let mut result = vec![counts.elements(&self.alphabet)?];
+
+        for _ in 0..steps {
+            counts = counts.step(&transitions)?;
+            result.push(counts.elements(&self.alphabet)?);
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyse() {
+        let polymer =
+            Polymer::parse("ABA\n\nAB -> A\nBA -> B\nCA -> D\n".as_bytes())
+                .unwrap();
+        let analysis = polymer.analyse();
+
+        assert_eq!(analysis.missing_rules, vec![['A', 'A'], ['B', 'B']]);
+        assert_eq!(analysis.absent, vec!['C', 'D']);
+        assert_eq!(analysis.unreachable.len(), 16 - 4);
+        assert!(analysis.unreachable.contains(&['C', 'A']));
+
+        // A and B are both inserted once per step, starting with 2 and 1
+        let trajectories = polymer.trajectories::<u32>(3).unwrap();
+        assert_eq!(
+            trajectories
+                .iter()
+                .map(|t| (t[0], t[1]))
+                .collect::<Vec<_>>(),
+            vec![(2, 1), (3, 2), (4, 3), (5, 4)]
+        );
+        assert!(trajectories.iter().all(|t| t[2] == 0 && t[3] == 0));
+    }
+}
@@ -0,0 +1,87 @@
+use super::Count;
+
+// Square matrix with checked arithmetic, stored in row major order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix<T> {
+    size: usize,
+    data: Vec<T>,
+}
+
+impl<T: Count> Matrix<T> {
+    pub fn identity(size: usize) -> Self {
+        let mut data = vec![T::zero(); size * size];
+        (0..size).for_each(|i| data[i * size + i] = T::one());
+        Self { size, data }
+    }
+
+    // Entry (to, from) counts how many times `from` becomes `to`
+    pub fn transitions(transitions: &[Vec<usize>]) -> Self {
+        let size = transitions.len();
+        let mut data = vec![T::zero(); size * size];
+
+        for (from, to) in transitions.iter().enumerate() {
+            for to in to {
+                let cell = &mut data[to * size + from];
+                *cell = cell.clone() + T::one();
+            }
+        }
+
+        Self { size, data }
+    }
+
+    // Zero entries are skipped, as transitions are sparse (at least for the
+    // first powers)
+    pub fn mul(&self, other: &Self) -> Option<Self> {
+        let n = self.size;
+        let mut data = vec![T::zero(); n * n];
+
+        for i in 0..n {
+            for k in 0..n {
+                let a = &self.data[i * n + k];
+                if a.is_zero() {
+                    continue;
+                }
+                for j in 0..n {
+                    let b = &other.data[k * n + j];
+                    if !b.is_zero() {
+                        let cell = &mut data[i * n + j];
+                        *cell = cell.checked_add(&a.checked_mul(b)?)?;
+                    }
+                }
+            }
+        }
+
+        Some(Self { size: n, data })
+    }
+
+    // Exponentiation by squaring, so `exp` steps take log2(exp) products
+    pub fn pow(&self, mut exp: usize) -> Option<Self> {
+        let mut result = Self::identity(self.size);
+        let mut base = self.clone();
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(&base)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.mul(&base)?;
+            }
+        }
+
+        Some(result)
+    }
+
+    pub fn apply(&self, vector: &[T]) -> Option<Vec<T>> {
+        let n = self.size;
+        (0..n)
+            .map(|i| {
+                (0..n).try_fold(T::zero(), |acc, j| {
+                    acc.checked_add(
+                        &self.data[i * n + j].checked_mul(&vector[j])?,
+                    )
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,522 @@
+mod analysis;
+mod matrix;
+
+use matrix::Matrix;
+
+use num::{CheckedAdd, CheckedMul, One, Zero};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::ops::Sub;
+
+// Numeric type used for the counts, which can be chosen depending on how
+// large the polymer grows (e.g. u64, u128 or BigUint)
+pub trait Count:
+    Clone + Zero + One + CheckedAdd + CheckedMul + Sub<Output = Self> + Ord
+{
+}
+
+impl<T> Count for T where
+    T: Clone + Zero + One + CheckedAdd + CheckedMul + Sub<Output = T> + Ord
+{
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolymerError {
+    // Counts do not fit in the numeric type after this step
+    Overflow(usize),
+}
+
+impl fmt::Display for PolymerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Overflow(step) => {
+                write!(f, "counts overflow at step {}", step)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolymerError {}
+
+// Elements present in the template or in the rules, indexed in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    elements: Vec<char>,
+    index: HashMap<char, usize>,
+}
+
+impl Alphabet {
+    pub fn new<I: IntoIterator<Item = char>>(elements: I) -> Self {
+        let mut elements = elements.into_iter().collect::<Vec<_>>();
+        elements.sort_unstable();
+        elements.dedup();
+
+        let index = elements.iter().enumerate().map(|(i, c)| (*c, i)).collect();
+        Self { elements, index }
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn elements(&self) -> &[char] {
+        &self.elements
+    }
+
+    pub fn element(&self, index: usize) -> char {
+        self.elements[index]
+    }
+
+    pub fn index(&self, element: char) -> Option<usize> {
+        self.index.get(&element).copied()
+    }
+
+    // Pairs are indexed as `first * len + second`
+    pub fn pair(&self, a: char, b: char) -> Option<usize> {
+        Some(self.index(a)? * self.len() + self.index(b)?)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Polymer {
+    pub template: Vec<char>,
+    pub rules: HashMap<[char; 2], char>,
+    pub alphabet: Alphabet,
+}
+
+impl Polymer {
+    pub fn new(template: Vec<char>, rules: HashMap<[char; 2], char>) -> Self {
+        let alphabet = Alphabet::new(
+            template
+                .iter()
+                .copied()
+                .chain(rules.iter().flat_map(|([a, b], c)| [*a, *b, *c])),
+        );
+
+        Self {
+            template,
+            rules,
+            alphabet,
+        }
+    }
+
+    pub fn parse<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut template = Vec::new();
+        let mut rules = HashMap::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            match index {
+                0 => template.extend(line.chars()),
+                _ if line.is_empty() => (),
+                _ => match line.split_once(" -> ") {
+                    Some((pair, insert)) => {
+                        let pair = pair.chars().collect::<Vec<_>>();
+                        rules.insert(
+                            pair.try_into().map_err(|_| invalid(index))?,
+                            insert.chars().next().ok_or(invalid(index))?,
+                        );
+                    }
+                    None => return Err(invalid(index)),
+                },
+            }
+        }
+
+        Ok(Self::new(template, rules))
+    }
+
+    // For each pair, the pairs it becomes after one step. Pairs without a
+    // rule stay as they are
+    pub fn transitions(&self) -> Vec<Vec<usize>> {
+        let n = self.alphabet.len();
+        let mut result = (0..n * n).map(|p| vec![p]).collect::<Vec<_>>();
+
+        for ([a, b], c) in &self.rules {
+            let pair = |x, y| self.alphabet.pair(x, y).unwrap();
+            result[pair(*a, *b)] = vec![pair(*a, *c), pair(*c, *b)];
+        }
+
+        result
+    }
+
+    pub fn counts<T: Count>(&self) -> PairCounts<T> {
+        let n = self.alphabet.len();
+        let mut pairs = vec![T::zero(); n * n];
+
+        for w in self.template.windows(2) {
+            let pair = self.alphabet.pair(w[0], w[1]).unwrap();
+            pairs[pair] = pairs[pair].clone() + T::one();
+        }
+
+        PairCounts {
+            pairs,
+            last: self.template.last().and_then(|c| self.alphabet.index(*c)),
+            step: 0,
+        }
+    }
+}
+
+// How the polymer is followed across steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    // Builds the actual polymer, whose length doubles on each step
+    Explicit,
+    // Only counts the pairs, so any number of steps can be computed
+    Pairs,
+}
+
+impl Mode {
+    // Largest polymer built when the mode is chosen automatically
+    const EXPLICIT_LENGTH: usize = 1 << 20;
+
+    // The length at most doubles on each step, so the template length times
+    // 2^steps bounds the polymer that would be built
+    pub fn auto(polymer: &Polymer, steps: usize) -> Self {
+        match u32::try_from(steps)
+            .ok()
+            .and_then(|steps| 1usize.checked_shl(steps))
+            .and_then(|f| f.checked_mul(polymer.template.len()))
+        {
+            Some(length) if length <= Self::EXPLICIT_LENGTH => Self::Explicit,
+            _ => Self::Pairs,
+        }
+    }
+}
+
+impl Polymer {
+    // Inserts the element of each pair in between, if it has a rule
+    pub fn step(&self, input: &[char]) -> Vec<char> {
+        let mut result = Vec::with_capacity(input.len() * 2);
+
+        for w in input.windows(2) {
+            result.push(w[0]);
+            result.extend(self.rules.get(&[w[0], w[1]]));
+        }
+        result.extend(input.last());
+
+        result
+    }
+
+    pub fn expand(&self, steps: usize) -> Vec<char> {
+        (0..steps).fold(self.template.clone(), |poly, _| self.step(&poly))
+    }
+
+    // Counts of each element after `steps`, following the alphabet order
+    pub fn elements<T: Count>(
+        &self,
+        steps: usize,
+        mode: Mode,
+    ) -> Result<Vec<T>, PolymerError> {
+        match mode {
+            Mode::Explicit => {
+                let mut result = vec![T::zero(); self.alphabet.len()];
+                for c in self.expand(steps) {
+                    let i = self.alphabet.index(c).unwrap();
+                    result[i] = result[i]
+                        .checked_add(&T::one())
+                        .ok_or(PolymerError::Overflow(steps))?;
+                }
+                Ok(result)
+            }
+            Mode::Pairs => {
+                let transitions = self.transitions();
+                let dims = transitions.len();
+                let mut counts = self.counts::<T>();
+
+                // Steps are applied one by one while that is cheaper than
+                // raising the transitions to the number of steps
+                let log = (usize::BITS - steps.leading_zeros()) as usize;
+                if steps <= dims * dims * log {
+                    for _ in 0..steps {
+                        counts = counts.step(&transitions)?;
+                    }
+                } else {
+                    counts = counts.steps(&transitions, steps)?;
+                }
+
+                counts.elements(&self.alphabet)
+            }
+        }
+    }
+}
+
+// Pairs that can be reached from the given ones by following the transitions
+fn reachable<I>(transitions: &[Vec<usize>], from: I) -> Vec<bool>
+where
+    I: IntoIterator<Item = usize>,
+{
+    let mut result = vec![false; transitions.len()];
+    let mut queue = from.into_iter().collect::<Vec<_>>();
+
+    while let Some(pair) = queue.pop() {
+        if !result[pair] {
+            result[pair] = true;
+            queue.extend(&transitions[pair]);
+        }
+    }
+
+    result
+}
+
+fn invalid(index: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Line {} with unknown format", index + 1),
+    )
+}
+
+// Number of times each pair of elements appears in the polymer. The last
+// element is kept, as it is the only one that is not the first of a pair
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairCounts<T> {
+    pub pairs: Vec<T>,
+    last: Option<usize>,
+    pub step: usize,
+}
+
+impl<T: Count> PairCounts<T> {
+    // Applies one step, only following the transitions of present pairs
+    pub fn step(
+        &self,
+        transitions: &[Vec<usize>],
+    ) -> Result<Self, PolymerError> {
+        let overflow = PolymerError::Overflow(self.step + 1);
+        let mut pairs = vec![T::zero(); self.pairs.len()];
+
+        for (from, count) in self.pairs.iter().enumerate() {
+            if count.is_zero() {
+                continue;
+            }
+            for to in &transitions[from] {
+                pairs[*to] =
+                    pairs[*to].checked_add(count).ok_or(overflow.clone())?;
+            }
+        }
+
+        Ok(Self {
+            pairs,
+            last: self.last,
+            step: self.step + 1,
+        })
+    }
+
+    // Applies `steps` steps at once, by raising the transitions to that power.
+    // Only the pairs reachable from the present ones are kept in the matrix,
+    // as the others could overflow without ever being part of the polymer
+    pub fn steps(
+        &self,
+        transitions: &[Vec<usize>],
+        steps: usize,
+    ) -> Result<Self, PolymerError> {
+        let overflow = PolymerError::Overflow(self.step + steps);
+        let present =
+            (0..self.pairs.len()).filter(|p| !self.pairs[*p].is_zero());
+        let kept = reachable(transitions, present)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(pair, reached)| reached.then_some(pair))
+            .collect::<Vec<_>>();
+
+        let mut index = vec![0; self.pairs.len()];
+        kept.iter()
+            .enumerate()
+            .for_each(|(i, pair)| index[*pair] = i);
+        let restricted = kept
+            .iter()
+            .map(|pair| {
+                transitions[*pair].iter().map(|to| index[*to]).collect()
+            })
+            .collect::<Vec<_>>();
+        let matrix = Matrix::transitions(&restricted)
+            .pow(steps)
+            .ok_or(overflow.clone())?;
+        let counts = kept
+            .iter()
+            .map(|pair| self.pairs[*pair].clone())
+            .collect::<Vec<_>>();
+
+        let mut pairs = vec![T::zero(); self.pairs.len()];
+        for (pair, count) in
+            kept.iter().zip(matrix.apply(&counts).ok_or(overflow)?)
+        {
+            pairs[*pair] = count;
+        }
+
+        Ok(Self {
+            pairs,
+            last: self.last,
+            step: self.step + steps,
+        })
+    }
+
+    // Counts of each element, following the alphabet order. Elements add up
+    // the counts of several pairs, so they can overflow even if pairs do not
+    pub fn elements(
+        &self,
+        alphabet: &Alphabet,
+    ) -> Result<Vec<T>, PolymerError> {
+        let overflow = PolymerError::Overflow(self.step);
+        let n = alphabet.len();
+        let mut result = vec![T::zero(); n];
+
+        for (pair, count) in self.pairs.iter().enumerate() {
+            result[pair / n] = result[pair / n]
+                .checked_add(count)
+                .ok_or(overflow.clone())?;
+        }
+        if let Some(last) = self.last {
+            result[last] =
+                result[last].checked_add(&T::one()).ok_or(overflow)?;
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::BigUint;
+
+    const EXAMPLE: &str = "NNCB
+
+CH -> B
+HH -> N
+CB -> H
+NH -> C
+HB -> C
+HC -> B
+HN -> C
+NN -> C
+BH -> H
+NC -> B
+NB -> B
+BN -> B
+BB -> N
+BC -> B
+CC -> N
+CN -> C";
+
+    fn spread<T: Count>(polymer: &Polymer, counts: &PairCounts<T>) -> T {
+        let elements = counts.elements(&polymer.alphabet).unwrap();
+        let present = elements.into_iter().filter(|c| !c.is_zero());
+        let (min, max) = present.fold(
+            (None, None),
+            |(min, max): (Option<T>, Option<T>), c| {
+                (
+                    Some(min.map_or(c.clone(), |m| m.min(c.clone()))),
+                    Some(max.map_or(c.clone(), |m| m.max(c.clone()))),
+                )
+            },
+        );
+        max.unwrap() - min.unwrap()
+    }
+
+    #[test]
+    fn test_steps() {
+        let polymer = Polymer::parse(EXAMPLE.as_bytes()).unwrap();
+        let transitions = polymer.transitions();
+        assert_eq!(polymer.alphabet.len(), 4);
+
+        let mut counts = polymer.counts::<u64>();
+        for _ in 0..10 {
+            counts = counts.step(&transitions).unwrap();
+        }
+        assert_eq!(spread(&polymer, &counts), 1588);
+
+        let counts = polymer.counts::<u64>().steps(&transitions, 40).unwrap();
+        assert_eq!(spread(&polymer, &counts), 2188189693529);
+    }
+
+    #[test]
+    fn test_modes() {
+        let polymer = Polymer::parse(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(
+            polymer.expand(2).into_iter().collect::<String>(),
+            "NBCCNBBBCBHCB"
+        );
+
+        for steps in 0..=10 {
+            assert_eq!(
+                polymer.elements::<u64>(steps, Mode::Explicit),
+                polymer.elements::<u64>(steps, Mode::Pairs),
+            );
+        }
+        assert_eq!(Mode::auto(&polymer, 10), Mode::Explicit);
+        assert_eq!(Mode::auto(&polymer, 40), Mode::Pairs);
+        assert_eq!(Mode::auto(&polymer, usize::MAX), Mode::Pairs);
+        assert_eq!(Mode::auto(&polymer, (1 << 32) + 1), Mode::Pairs);
+
+        let single = Polymer::parse("N\n\nNN -> C\n".as_bytes()).unwrap();
+        assert_eq!(Mode::auto(&single, 20), Mode::Explicit);
+        assert_eq!(Mode::auto(&single, 40), Mode::Pairs);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let polymer = Polymer::parse(EXAMPLE.as_bytes()).unwrap();
+        let transitions = polymer.transitions();
+
+        assert_eq!(
+            polymer.counts::<u64>().steps(&transitions, 100),
+            Err(PolymerError::Overflow(100))
+        );
+
+        // The polymer doubles its length (minus one) on each step
+        let counts = polymer
+            .counts::<BigUint>()
+            .steps(&transitions, 100)
+            .unwrap();
+        let length = counts
+            .elements(&polymer.alphabet)
+            .unwrap()
+            .into_iter()
+            .fold(BigUint::zero(), |acc, c| acc + c);
+        assert_eq!(length, BigUint::from(3u8) * (BigUint::one() << 100) + 1u8);
+    }
+
+    #[test]
+    fn test_unreachable_growth() {
+        // CC doubles on each step, but it is never part of the polymer
+        let polymer = Polymer::parse("AB\n\nCC -> C\n".as_bytes()).unwrap();
+        let transitions = polymer.transitions();
+
+        let counts = polymer.counts::<u64>().steps(&transitions, 100).unwrap();
+        assert_eq!(counts.elements(&polymer.alphabet), Ok(vec![1, 1, 0]));
+        assert_eq!(counts.step, 100);
+    }
+
+    #[test]
+    fn test_element_overflow() {
+        // No pair appears more than 199 times, but A starts 299 of them and
+        // is also the last element
+        let template = "A".repeat(200) + &"BA".repeat(100);
+        let polymer = Polymer::new(template.chars().collect(), HashMap::new());
+
+        let counts = polymer.counts::<u8>();
+        assert_eq!(counts.pairs.iter().max(), Some(&199));
+        assert_eq!(
+            counts.elements(&polymer.alphabet),
+            Err(PolymerError::Overflow(0))
+        );
+        assert_eq!(
+            polymer.elements::<u8>(0, Mode::Pairs),
+            Err(PolymerError::Overflow(0))
+        );
+        assert_eq!(polymer.elements::<u16>(0, Mode::Pairs), Ok(vec![300, 100]));
+    }
+
+    #[test]
+    fn test_missing_rules() {
+        let polymer = Polymer::parse("ABA\n\nAB -> A\n".as_bytes()).unwrap();
+        assert_eq!(polymer.expand(2).into_iter().collect::<String>(), "AAABA");
+
+        for steps in 0..=4 {
+            assert_eq!(
+                polymer.elements::<u64>(steps, Mode::Explicit),
+                polymer.elements::<u64>(steps, Mode::Pairs),
+            );
+        }
+    }
+}
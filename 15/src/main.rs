@@ -1,6 +1,9 @@
+mod route;
+
+use common::image::{ColourRamp, Image, Rgb};
 use grid::Grid;
-use num::Bounded;
-use priority_queue::PriorityQueue;
+use itertools::Itertools;
+use route::{Heuristic, Moves, Search, Tiled, WeightMap};
 use std::{fs::File, io::BufRead, io::BufReader};
 
 fn grid_parse(filepath: &str) -> Grid<u8> {
@@ -22,7 +25,10 @@ fn grid_parse(filepath: &str) -> Grid<u8> {
                 // >> To be converted to a vector of digits (from 0 to 1)
                 //    representing the weight of the path finding graph
                 // >> This will fail if the rows don't have the same number of elements
-                .map(|digit| digit.to_digit(10).unwrap() as u8)
+                .map(|digit| match digit.to_digit(10) {
+                    Some(d @ 1..=9) => d as u8,
+                    _ => panic!("Risk level '{}' is not in 1-9", digit),
+                })
                 .collect::<Vec<u8>>(),
         );
     }
@@ -34,148 +40,35 @@ fn grid_parse(filepath: &str) -> Grid<u8> {
     results
 }
 
-// TODO: Make this function generic on u8
-fn grid_expand(origin: Grid<u8>, factor: usize) -> Grid<u8> {
-    // Return input grid if the factor is equal to one
-    if factor == 1 {
-        return origin;
-    }
-
-    // Otherwise, create a new grid with a size determined by 'factor'
-    let mut result = Grid::new(origin.rows() * factor, origin.cols() * factor);
-
-    // Cycle all tiles
-    for row_tile in 0..factor {
-        for col_tile in 0..factor {
-            // We have to add +1 each time we go downwards/eastwards a tile
-            // Tile (0, 0) is exactly the same as the original map
-            let factor_tile = (row_tile + col_tile) as u8;
-
-            // For all values in the current tile
-            for r in 0..origin.rows() {
-                for c in 0..origin.cols() {
-                    // Add the tile factor to the original weight
-                    let mut val = origin[r][c] + factor_tile;
-                    // If the value exceeds 9, wrap it (9->1)
-                    if val > 9 {
-                        val = val - 9;
-                    }
-
-                    // Keep new value in the map containing all tiles
-                    result[row_tile * origin.rows() + r][col_tile * origin.cols() + c] = val;
-                }
-            }
-        }
-    }
-
-    // Return result
-    result
+fn parse_cell(cell: &str) -> (usize, usize) {
+    let (r, c) = cell.split_once(',').expect("Cell is not 'row,col'");
+    (
+        r.parse().expect("Invalid row"),
+        c.parse().expect("Invalid column"),
+    )
 }
 
-fn calc_priority<T>(value: T) -> T
-where
-    T: Bounded + std::ops::Sub<T, Output = T>,
-{
-    T::max_value() - value
+// Cell given as `row,col` after an option
+fn cell_option(name: &str) -> Option<(usize, usize)> {
+    common::args::option(name).map(|v| parse_cell(&v))
 }
 
-// TODO: Make generic on u64
-fn path_finding_algorithm<T>(weights: &Grid<T>) -> Grid<Option<u64>>
-where
-    T: Copy,
-    u64: From<T>,
-{
-    // Return if inputs are empty
-    let rows = weights.rows();
-    let cols = weights.cols();
-    if rows == 0 || cols == 0 {
-        return Grid::new(0, 0);
-    }
-
-    // Create list of distances, where the origin of the graph is the top-left corner
-    // and the target of the grapth is the bottom right
-    let mut distances: Grid<Option<u64>> = Grid::init(weights.rows(), weights.cols(), None);
-    distances[0][0] = Some(0);
-
-    // Create first candidate candidate (we start at the (0,0) vertex)
-    let mut candidates = PriorityQueue::new();
-    candidates.push((0, 0), calc_priority(distances[0][0].unwrap()));
-
-    // Continue the algorithm until we've runned out of candidates
-    let mut counter = 0;
-    let mut max_candidates = 0;
-    while let Some(((r, c), _)) = candidates.pop() {
-        // If we are in the target vertex, stop
-        if r == (rows - 1) && c == (cols - 1) {
-            candidates.clear();
-            break;
-        }
-
-        // Check right vicinity
-        if c < cols - 1 && distances[r][c + 1].is_none() {
-            // Calculate new distance
-            let score = u64::from(weights[r][c + 1]) + distances[r][c].unwrap();
-            distances[r][c + 1] = Some(score);
-
-            // Put new candidate in the processing queue
-            candidates.push((r, c + 1), calc_priority(score));
-        }
-
-        // Check lower vicinity
-        if r < rows - 1 && distances[r + 1][c].is_none() {
-            // Calculate new distance
-            let score = u64::from(weights[r + 1][c]) + distances[r][c].unwrap();
-            distances[r + 1][c] = Some(score);
-
-            // Put new candidate in the processing queue
-            candidates.push((r + 1, c), calc_priority(score));
-        }
-
-        // Check left vicinity
-        if c > 0 && distances[r][c - 1].is_none() {
-            // Calculate new distance
-            let score = u64::from(weights[r][c - 1]) + distances[r][c].unwrap();
-            distances[r][c - 1] = Some(score);
-
-            // Put new candidate in the processing queue
-            candidates.push((r, c - 1), calc_priority(score));
-        }
-
-        // Check upper vicinity
-        if r > 0 && distances[r - 1][c].is_none() {
-            // Calculate new distance
-            let score = u64::from(weights[r - 1][c]) + distances[r][c].unwrap();
-            distances[r - 1][c] = Some(score);
-
-            // Put new candidate in the processing queue
-            candidates.push((r - 1, c), calc_priority(score));
+// Risk levels in greyscale, with the path overlaid in red
+fn render_path<W: WeightMap>(weights: &W, path: &[(usize, usize)]) -> Image {
+    let ramp = ColourRamp::greyscale();
+    let mut image = Image::new(weights.rows(), weights.cols(), 4);
+    for r in 0..weights.rows() {
+        for c in 0..weights.cols() {
+            let w = weights.weight(r, c) as f64;
+            image.set(r, c, ramp.scaled(w, 0.0, 9.0));
         }
-
-        if candidates.len() > max_candidates {
-            max_candidates = candidates.len();
-        }
-
-        // Progress print
-        counter = counter + 1;
-        println!(
-            "Progress {:.2} (queue={}/{},processed={})",
-            counter as f32 / ((rows * cols) as f32),
-            candidates.len(),
-            max_candidates,
-            counter,
-        );
     }
 
-    // Print final result
-    println!(
-        "Final result in ({},{}) is {:?}",
-        rows - 1,
-        cols - 1,
-        distances[rows - 1][cols - 1]
-    );
+    for (r, c) in path {
+        image.overlay(*r, *c, Rgb(255, 0, 0), 0.8);
+    }
 
-    // Return the distances map
-    distances
+    image
 }
 
 fn main() {
@@ -187,6 +80,7 @@ fn main() {
     // Parse scale from second argument (optional, default=1)
     let scale: usize = std::env::args()
         .nth(2)
+        .filter(|a| !a.starts_with("--"))
         .unwrap_or("1".to_string())
         .parse()
         .unwrap();
@@ -194,9 +88,69 @@ fn main() {
     // Read the path fiding grid, contaning weights for each node to be visited
     let weights = grid_parse(&filepath);
 
-    // Scale map if required by input arguments
-    let weights = grid_expand(weights, scale);
+    // Scale map if required by input arguments (tiles are not stored)
+    let weights = Tiled::new(&weights, scale);
+
+    // The heuristic only changes how many cells are explored
+    let heuristic = match common::args::option("--heuristic").as_deref() {
+        None | Some("none") => Heuristic::None,
+        Some("manhattan") => Heuristic::Manhattan,
+        Some(h) => panic!("Unknown heuristic {}", h),
+    };
+
+    let moves = match common::args::option("--moves").as_deref() {
+        None | Some("orthogonal") => Moves::Orthogonal,
+        Some("king") => Moves::King,
+        Some("knight") => Moves::Knight,
+        Some(m) => panic!("Unknown moves {}", m),
+    };
+
+    let mut search = Search::new().with_heuristic(heuristic).with_moves(moves);
+    if let Some(cell) = cell_option("--start") {
+        search = search.with_start(cell);
+    }
+    if let Some(cell) = cell_option("--goal") {
+        search = search.with_goal(cell);
+    }
+    for cell in common::args::option("--block")
+        .iter()
+        .flat_map(|v| v.split('/'))
+    {
+        search = search.with_blocked(parse_cell(cell));
+    }
+
+    // Alternatives are only searched for when asked for
+    let k = common::args::option("--alternatives")
+        .map(|k| k.parse().expect("Invalid number of alternatives"))
+        .unwrap_or(1);
+    let routes = search.k_shortest_paths(&weights, k);
+
+    match routes.first() {
+        Some(route) => println!("Result: {}", route.cost),
+        None => println!("Result: no path"),
+    }
+    for (i, route) in routes.iter().enumerate().skip(1) {
+        println!("Alternative #{}: {}", i, route.cost);
+    }
 
-    // Create grid of distances
-    path_finding_algorithm(&weights);
+    if common::args::flag("--path") {
+        for route in &routes {
+            println!(
+                "Path: {}",
+                route
+                    .path
+                    .iter()
+                    .map(|(r, c)| format!("{},{}", r, c))
+                    .join(" ")
+            );
+        }
+    }
+
+    if let (Some(route), Some(output)) =
+        (routes.first(), common::args::option("--ppm"))
+    {
+        render_path(&weights, &route.path)
+            .save_ppm(&output)
+            .expect("Error while writing image");
+    }
 }
@@ -0,0 +1,536 @@
+mod tiled;
+
+pub use tiled::Tiled;
+
+use grid::Grid;
+use priority_queue::PriorityQueue;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+// Cost of entering each cell of a rectangular map
+pub trait WeightMap {
+    fn rows(&self) -> usize;
+    fn cols(&self) -> usize;
+    fn weight(&self, row: usize, col: usize) -> u64;
+
+    fn lowest(&self) -> Option<u64> {
+        (0..self.rows())
+            .flat_map(|r| (0..self.cols()).map(move |c| (r, c)))
+            .map(|(r, c)| self.weight(r, c))
+            .min()
+    }
+}
+
+impl<T> WeightMap for Grid<T>
+where
+    T: Copy,
+    u64: From<T>,
+{
+    fn rows(&self) -> usize {
+        Grid::rows(self)
+    }
+
+    fn cols(&self) -> usize {
+        Grid::cols(self)
+    }
+
+    fn weight(&self, row: usize, col: usize) -> u64 {
+        u64::from(self[row][col])
+    }
+}
+
+// Lower bound of the cost still needed to reach the goal, used to explore
+// the cells closer to it first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heuristic {
+    // Plain Dijkstra's algorithm
+    None,
+    // Manhattan distance (or the fewest moves needed with other move sets)
+    // times the lowest weight (A*)
+    Manhattan,
+}
+
+// Cells that can be reached from another in one move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Moves {
+    // Up, down, left and right
+    Orthogonal,
+    // Also the diagonals, as a chess king
+    King,
+    // L shaped jumps, as a chess knight
+    Knight,
+}
+
+impl Moves {
+    fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Self::Orthogonal => &[(0, 1), (1, 0), (0, -1), (-1, 0)],
+            Self::King => &[
+                (0, 1),
+                (1, 1),
+                (1, 0),
+                (1, -1),
+                (0, -1),
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+            ],
+            Self::Knight => &[
+                (1, 2),
+                (2, 1),
+                (2, -1),
+                (1, -2),
+                (-1, -2),
+                (-2, -1),
+                (-2, 1),
+                (-1, 2),
+            ],
+        }
+    }
+
+    // Fewest moves between two cells (or less), on an unbounded map
+    fn distance(&self, a: (usize, usize), b: (usize, usize)) -> u64 {
+        let dr = a.0.abs_diff(b.0) as u64;
+        let dc = a.1.abs_diff(b.1) as u64;
+        match self {
+            Self::Orthogonal => dr + dc,
+            Self::King => dr.max(dc),
+            Self::Knight => dr.max(dc).div_ceil(2).max((dr + dc).div_ceil(3)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    // Sum of the weights of the cells entered (the start is not entered)
+    pub cost: u64,
+    // Cells from the start to the goal, both included
+    pub path: Vec<(usize, usize)>,
+}
+
+type Cell = (usize, usize);
+
+// Search state of a cell reached so far, which are the only ones stored
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    distance: u64,
+    // Index of the move that entered the cell (None for the start)
+    entered_by: Option<u8>,
+    settled: bool,
+}
+
+// Route query over a map. By default, routes go from the top-left to the
+// bottom-right corner with orthogonal moves
+#[derive(Debug, Clone)]
+pub struct Search {
+    start: Cell,
+    goal: Option<Cell>,
+    moves: Moves,
+    blocked: HashSet<Cell>,
+    heuristic: Heuristic,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self {
+            start: (0, 0),
+            goal: None,
+            moves: Moves::Orthogonal,
+            blocked: HashSet::new(),
+            heuristic: Heuristic::None,
+        }
+    }
+}
+
+impl Search {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_start(mut self, cell: Cell) -> Self {
+        self.start = cell;
+        self
+    }
+
+    pub fn with_goal(mut self, cell: Cell) -> Self {
+        self.goal = Some(cell);
+        self
+    }
+
+    pub fn with_moves(mut self, moves: Moves) -> Self {
+        self.moves = moves;
+        self
+    }
+
+    // Impassable cell, which routes can not enter
+    pub fn with_blocked(mut self, cell: Cell) -> Self {
+        self.blocked.insert(cell);
+        self
+    }
+
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    fn goal<W: WeightMap>(&self, weights: &W) -> Cell {
+        self.goal
+            .unwrap_or((weights.rows() - 1, weights.cols() - 1))
+    }
+
+    // Lowest weight of the map, which is only needed by the heuristic. None
+    // if the map is empty
+    fn lowest<W: WeightMap>(&self, weights: &W) -> Option<u64> {
+        match self.heuristic {
+            Heuristic::None => Some(0),
+            Heuristic::Manhattan => weights.lowest(),
+        }
+    }
+
+    // Cheapest route, if the goal can be reached
+    #[allow(dead_code)]
+    pub fn shortest_path<W: WeightMap>(&self, weights: &W) -> Option<Route> {
+        let lowest = self.lowest(weights)?;
+        self.search(
+            weights,
+            lowest,
+            self.start,
+            &HashSet::new(),
+            &HashSet::new(),
+        )
+    }
+
+    // Cheapest route from `start`, avoiding some cells and moves on top of
+    // the blocked ones. Cells are only settled when popped from the queue, so
+    // their distance is final by then
+    fn search<W: WeightMap>(
+        &self,
+        weights: &W,
+        lowest: u64,
+        start: Cell,
+        avoid: &HashSet<Cell>,
+        avoid_moves: &HashSet<(Cell, Cell)>,
+    ) -> Option<Route> {
+        let rows = weights.rows();
+        let cols = weights.cols();
+        if rows == 0 || cols == 0 {
+            return None;
+        }
+
+        let goal = self.goal(weights);
+        let passable = |(r, c): Cell| {
+            r < rows
+                && c < cols
+                && !self.blocked.contains(&(r, c))
+                && !avoid.contains(&(r, c))
+        };
+        if !passable(start) || !passable(goal) {
+            return None;
+        }
+
+        let estimate = |cell: Cell| match self.heuristic {
+            Heuristic::None => 0,
+            Heuristic::Manhattan => self.moves.distance(cell, goal) * lowest,
+        };
+
+        // Only the cells reached so far are stored, rather than a node for
+        // every cell of the map (e.g. when it is tiled)
+        let offsets = self.moves.offsets();
+        let mut nodes = HashMap::from([(
+            start,
+            Node {
+                distance: 0,
+                entered_by: None,
+                settled: false,
+            },
+        )]);
+        let mut candidates = PriorityQueue::new();
+        candidates.push(start, Reverse(estimate(start)));
+
+        while let Some(((r, c), _)) = candidates.pop() {
+            if (r, c) == goal {
+                break;
+            }
+            let node = nodes.get_mut(&(r, c)).unwrap();
+            node.settled = true;
+            let distance = node.distance;
+
+            for (index, (dr, dc)) in offsets.iter().enumerate() {
+                let next =
+                    (r.wrapping_add_signed(*dr), c.wrapping_add_signed(*dc));
+                if !passable(next) || avoid_moves.contains(&((r, c), next)) {
+                    continue;
+                }
+
+                let score = distance + weights.weight(next.0, next.1);
+                let entered = Node {
+                    distance: score,
+                    entered_by: Some(index as u8),
+                    settled: false,
+                };
+                match nodes.get_mut(&next) {
+                    Some(n) if n.settled || score >= n.distance => continue,
+                    Some(n) => *n = entered,
+                    None => {
+                        nodes.insert(next, entered);
+                    }
+                }
+                candidates.push_increase(next, Reverse(score + estimate(next)));
+            }
+        }
+
+        let cost = nodes.get(&goal)?.distance;
+        let mut path = vec![goal];
+        let mut cell = goal;
+        while let Some(index) = nodes[&cell].entered_by {
+            let (dr, dc) = offsets[index as usize];
+            cell = (
+                cell.0.wrapping_add_signed(-dr),
+                cell.1.wrapping_add_signed(-dc),
+            );
+            path.push(cell);
+        }
+        path.reverse();
+
+        Some(Route { cost, path })
+    }
+
+    // Up to `k` cheapest routes that do not visit a cell twice, cheapest
+    // first (Yen's algorithm). Each route deviates from a previous one at
+    // some cell, and follows the cheapest route from there that avoids the
+    // cells before and the moves already taken by the other routes
+    pub fn k_shortest_paths<W: WeightMap>(
+        &self,
+        weights: &W,
+        k: usize,
+    ) -> Vec<Route> {
+        let mut result: Vec<Route> = Vec::new();
+        let mut candidates: Vec<Route> = Vec::new();
+
+        // The lowest weight is shared by all the searches
+        let lowest = match self.lowest(weights) {
+            Some(lowest) => lowest,
+            None => return result,
+        };
+        let (avoid, avoid_moves) = (HashSet::new(), HashSet::new());
+        if let Some(route) =
+            self.search(weights, lowest, self.start, &avoid, &avoid_moves)
+        {
+            candidates.push(route);
+        }
+
+        while result.len() < k && !candidates.is_empty() {
+            let best = (0..candidates.len())
+                .min_by_key(|i| candidates[*i].cost)
+                .unwrap();
+            let route = candidates.swap_remove(best);
+
+            // Deviations are only needed for the routes still to be found
+            if result.len() + 1 == k {
+                result.push(route);
+                break;
+            }
+
+            let mut root_cost = 0;
+            for i in 0..route.path.len() - 1 {
+                let root = &route.path[..=i];
+                if i > 0 {
+                    root_cost += weights.weight(root[i].0, root[i].1);
+                }
+
+                let avoid = root[..i].iter().copied().collect();
+                let avoid_moves = result
+                    .iter()
+                    .chain(std::iter::once(&route))
+                    .filter(|r| r.path.len() > i + 1 && r.path[..=i] == *root)
+                    .map(|r| (r.path[i], r.path[i + 1]))
+                    .collect();
+
+                if let Some(spur) =
+                    self.search(weights, lowest, root[i], &avoid, &avoid_moves)
+                {
+                    let path = root[..i]
+                        .iter()
+                        .chain(spur.path.iter())
+                        .copied()
+                        .collect::<Vec<_>>();
+
+                    let known = result.iter().chain(candidates.iter());
+                    if !known.clone().any(|c| c.path == path) {
+                        candidates.push(Route {
+                            cost: root_cost + spur.cost,
+                            path,
+                        });
+                    }
+                }
+            }
+
+            result.push(route);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // Costs of all the simple routes of a search, cheapest first
+    fn brute_force(search: &Search, weights: &Grid<u8>) -> Vec<u64> {
+        fn visit(
+            search: &Search,
+            weights: &Grid<u8>,
+            visited: &mut Grid<bool>,
+            (r, c): Cell,
+            cost: u64,
+            result: &mut Vec<u64>,
+        ) {
+            if (r, c) == search.goal(weights) {
+                result.push(cost);
+                return;
+            }
+
+            for (dr, dc) in search.moves.offsets() {
+                let (nr, nc) =
+                    (r.wrapping_add_signed(*dr), c.wrapping_add_signed(*dc));
+                if nr < weights.rows()
+                    && nc < weights.cols()
+                    && !visited[nr][nc]
+                    && !search.blocked.contains(&(nr, nc))
+                {
+                    visited[nr][nc] = true;
+                    let w = weights[nr][nc] as u64;
+                    visit(search, weights, visited, (nr, nc), cost + w, result);
+                    visited[nr][nc] = false;
+                }
+            }
+        }
+
+        let mut result = Vec::new();
+        if !search.blocked.contains(&search.start) {
+            let mut visited = Grid::init(weights.rows(), weights.cols(), false);
+            visited[search.start.0][search.start.1] = true;
+            visit(search, weights, &mut visited, search.start, 0, &mut result);
+        }
+        result.sort_unstable();
+        result
+    }
+
+    fn check(search: &Search, weights: &Grid<u8>) {
+        let expected = brute_force(search, weights);
+        let route = search.shortest_path(weights);
+        assert_eq!(route.as_ref().map(|r| r.cost), expected.first().copied());
+
+        // The path is made of valid moves, and its cost matches
+        if let Some(route) = route {
+            assert_eq!(route.path.first(), Some(&search.start));
+            assert_eq!(route.path.last(), Some(&search.goal(weights)));
+            assert!(route.path.windows(2).all(|w| {
+                let offset = (
+                    w[1].0 as isize - w[0].0 as isize,
+                    w[1].1 as isize - w[0].1 as isize,
+                );
+                search.moves.offsets().contains(&offset)
+            }));
+            assert_eq!(
+                route.path[1..]
+                    .iter()
+                    .map(|&(r, c)| weights[r][c] as u64)
+                    .sum::<u64>(),
+                route.cost
+            );
+        }
+
+        // Alternatives are distinct simple routes, with the cheapest costs
+        let routes = search.k_shortest_paths(weights, 5);
+        assert_eq!(
+            routes.iter().map(|r| r.cost).collect::<Vec<_>>(),
+            expected.iter().copied().take(5).collect::<Vec<_>>()
+        );
+        assert!(routes.iter().all(|r| r.path.iter().all_unique()));
+        assert!(routes.iter().map(|r| &r.path).all_unique());
+    }
+
+    fn random_grid(rng: &mut StdRng, max: usize) -> Grid<u8> {
+        let rows = rng.gen_range(1..=max);
+        let cols = rng.gen_range(1..=max);
+        Grid::from_vec(
+            (0..rows * cols).map(|_| rng.gen_range(1..=9)).collect(),
+            cols,
+        )
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let mut rng = StdRng::seed_from_u64(15);
+
+        for _ in 0..50 {
+            let weights = random_grid(&mut rng, 4);
+            for heuristic in [Heuristic::None, Heuristic::Manhattan] {
+                check(&Search::new().with_heuristic(heuristic), &weights);
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_options() {
+        let mut rng = StdRng::seed_from_u64(45);
+
+        for _ in 0..50 {
+            let weights = random_grid(&mut rng, 3);
+            let cell = |rng: &mut StdRng| {
+                (
+                    rng.gen_range(0..weights.rows()),
+                    rng.gen_range(0..weights.cols()),
+                )
+            };
+
+            let search = Search::new()
+                .with_start(cell(&mut rng))
+                .with_goal(cell(&mut rng))
+                .with_blocked(cell(&mut rng))
+                .with_heuristic(Heuristic::Manhattan);
+
+            for moves in [Moves::Orthogonal, Moves::King, Moves::Knight] {
+                check(&search.clone().with_moves(moves), &weights);
+            }
+        }
+    }
+
+    #[test]
+    fn test_detour() {
+        // The cheapest route snakes around the walls of 9s
+        let weights = Grid::from_vec(
+            vec![
+                1, 1, 1, 1, //
+                9, 9, 9, 1, //
+                1, 1, 1, 1, //
+                1, 9, 9, 9, //
+                1, 1, 1, 1, //
+            ],
+            4,
+        );
+        let search = Search::new().with_heuristic(Heuristic::Manhattan);
+
+        assert_eq!(search.shortest_path(&weights).unwrap().cost, 13);
+        check(&search, &weights);
+
+        // Unless the way is blocked
+        let search = search.with_blocked((1, 3));
+        assert_eq!(search.shortest_path(&weights).unwrap().cost, 15);
+    }
+
+    #[test]
+    fn test_empty() {
+        let weights = Grid::<u8>::new(0, 0);
+
+        for heuristic in [Heuristic::None, Heuristic::Manhattan] {
+            let search = Search::new().with_heuristic(heuristic);
+            assert_eq!(search.shortest_path(&weights), None);
+            assert_eq!(search.k_shortest_paths(&weights, 3), vec![]);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use super::WeightMap;
+use grid::Grid;
+use num::PrimInt;
+
+// Map repeated `factor` times in each direction, where the values of each
+// tile are one more than the tile to its left or above, wrapping 9 back to 1.
+// Values are computed on access, so the tiles are never stored. Risk levels
+// are in [1, 9], so 0 is not a valid value in the base map
+#[derive(Debug, Clone, Copy)]
+pub struct Tiled<'a, T> {
+    base: &'a Grid<T>,
+    factor: usize,
+}
+
+impl<'a, T: PrimInt> Tiled<'a, T> {
+    pub fn new(base: &'a Grid<T>, factor: usize) -> Self {
+        debug_assert!(factor > 0);
+        Self { base, factor }
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> T {
+        let (rows, cols) = self.base.size();
+        debug_assert!(row < rows * self.factor && col < cols * self.factor);
+
+        // Tiles are reduced first, so the sum fits in small types
+        let tile = (row / rows + col / cols) % 9;
+        let base = self.base[row % rows][col % cols].to_usize().unwrap();
+        debug_assert!((1..=9).contains(&base));
+        T::from((base + tile - 1) % 9 + 1).unwrap()
+    }
+}
+
+impl<T: PrimInt> WeightMap for Tiled<'_, T> {
+    fn rows(&self) -> usize {
+        self.base.rows() * self.factor
+    }
+
+    fn cols(&self) -> usize {
+        self.base.cols() * self.factor
+    }
+
+    fn weight(&self, row: usize, col: usize) -> u64 {
+        self.get(row, col).to_u64().unwrap()
+    }
+
+    // Every value appears within the first 9 tiles of a row
+    fn lowest(&self) -> Option<u64> {
+        let cols = self.base.cols() * self.factor.min(9);
+        (0..self.base.rows())
+            .flat_map(|r| (0..cols).map(move |c| (r, c)))
+            .map(|(r, c)| self.weight(r, c))
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiled() {
+        let base = Grid::from_vec(vec![8u8, 1], 2);
+        let tiled = Tiled::new(&base, 5);
+
+        assert_eq!((tiled.rows(), tiled.cols()), (5, 10));
+        assert_eq!(
+            (0..10).map(|c| tiled.get(0, c)).collect::<Vec<_>>(),
+            vec![8, 1, 9, 2, 1, 3, 2, 4, 3, 5]
+        );
+        assert_eq!(
+            (0..5).map(|r| tiled.get(r, 1)).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+        assert_eq!(tiled.lowest(), Some(1));
+
+        // Tiles far away do not overflow the cell type
+        let tiled = Tiled::new(&base, 1000);
+        assert_eq!(tiled.get(999, 1998), 8);
+    }
+}
@@ -1,26 +1,25 @@
 mod packet;
 
-use packet::PacketFactory;
+use num::BigUint;
+use packet::{
+    encode, evaluate, render, Bits, EvalError, Expr, LengthType, PacketFactory,
+};
 
 use std::{fs::File, io::BufRead, io::BufReader};
 
-fn parse(filepath: &str) -> Vec<bool> {
+fn parse(filepath: &str) -> Bits {
     let file = File::open(filepath).expect("Error while opening cave file");
     let reader = BufReader::new(file);
 
-    reader
-        .lines()
-        .next()
-        .unwrap()
-        .unwrap()
-        // Convert each char into a 4 bit array of bool
-        .chars()
-        .map(|c| {
-            let value = c.to_digit(16).unwrap() as u8;
-            (0..4).rev().map(|i| (value & (1 << i)) != 0).collect()
-        })
-        .reduce(|acc, e| [acc, e].concat())
-        .unwrap()
+    Bits::from_hex(&reader.lines().next().unwrap().unwrap())
+        .expect("Transmission is not hexadecimal")
+}
+
+fn display<V: std::fmt::Display>(value: Result<V, EvalError>) -> String {
+    match value {
+        Ok(value) => value.to_string(),
+        Err(e) => format!("Error: {}", e),
+    }
 }
 
 fn main() {
@@ -29,7 +28,13 @@ fn main() {
         .expect("Filepath for input not provided");
 
     let bitstream = parse(&filepath);
-    let root_packet = PacketFactory::factory(&bitstream);
+    let root_packet = match PacketFactory::factory(bitstream.reader()) {
+        Ok(packet) => packet,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
 
     println!(
         "Problem #1: {}",
@@ -39,5 +44,34 @@ fn main() {
             .map(|v| v as u64)
             .sum::<u64>()
     );
-    println!("Problem #2: {:?}", root_packet.compute());
+    // Wider backends are only needed for transmissions with huge values
+    let value = match common::args::option("--numeric").as_deref() {
+        None | Some("u64") => display(evaluate::<u64>(root_packet.as_ref())),
+        Some("u128") => display(evaluate::<u128>(root_packet.as_ref())),
+        Some("big") => display(evaluate::<BigUint>(root_packet.as_ref())),
+        Some(n) => panic!("Unknown numeric backend {}", n),
+    };
+    println!("Problem #2: {}", value);
+
+    match common::args::option("--print").as_deref() {
+        None => (),
+        Some("tree") => print!("{}", render::tree(root_packet.as_ref())),
+        Some("sexpr") => println!("{}", render::sexpr(root_packet.as_ref())),
+        Some("infix") => println!("{}", render::infix(root_packet.as_ref())),
+        Some(f) => panic!("Unknown print format {}", f),
+    }
+
+    // Transmission of the decoded packet, with the given length type
+    if let Some(length) = common::args::option("--encode") {
+        let length = match length.as_str() {
+            "bits" => LengthType::Bits,
+            "count" => LengthType::Count,
+            "auto" => LengthType::Auto,
+            l => panic!("Unknown length type {}", l),
+        };
+        match encode(&Expr::from_packet(root_packet.as_ref()), length) {
+            Ok(bits) => println!("Encoded: {}", bits.to_hex()),
+            Err(e) => println!("Encoded: {}", e),
+        }
+    }
 }
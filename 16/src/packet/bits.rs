@@ -0,0 +1,153 @@
+// Bit string packed in bytes, most significant bit first, as transmissions
+// are written in hexadecimal
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bits {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl Bits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let mut result = Self::new();
+        for c in hex.trim().chars() {
+            result.push(c.to_digit(16)? as u64, 4);
+        }
+        Some(result)
+    }
+
+    // Padding the last byte with zeros
+    pub fn to_hex(&self) -> String {
+        self.bytes.iter().map(|b| format!("{:02X}", b)).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[allow(dead_code)]
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn reader(&self) -> BitReader<'_> {
+        BitReader::new(&self.bytes, self.len)
+    }
+
+    // Appends the `width` lowest bits of `value`
+    pub fn push(&mut self, value: u64, width: usize) {
+        debug_assert!(width <= 64);
+
+        for i in (0..width).rev() {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            let bit = ((value >> i) & 1) as u8;
+            *self.bytes.last_mut().unwrap() |= bit << (7 - self.len % 8);
+            self.len += 1;
+        }
+    }
+
+    pub fn extend(&mut self, other: &Self) {
+        let mut reader = other.reader();
+        while reader.remaining() > 0 {
+            let width = reader.remaining().min(64);
+            self.push(reader.read_bits(width).unwrap(), width);
+        }
+    }
+}
+
+// Reads integers of any width (up to 64 bits) from a bit string
+#[derive(Debug, Clone)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    len: usize,
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    // Only the first `len` bits of `bytes` are read
+    pub fn new(bytes: &'a [u8], len: usize) -> Self {
+        debug_assert!(len <= bytes.len() * 8);
+        Self {
+            bytes,
+            len,
+            position: 0,
+        }
+    }
+
+    // Bits read so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.len - self.position
+    }
+
+    // Next `n` bits as an integer, most significant first. Nothing is read
+    // if there are less than `n` bits left
+    pub fn read_bits(&mut self, n: usize) -> Option<u64> {
+        debug_assert!(n <= 64);
+        if n > self.remaining() {
+            return None;
+        }
+
+        // Takes as many bits as possible from each byte
+        let mut result = 0u64;
+        let mut n = n;
+        while n > 0 {
+            let available = 8 - self.position % 8;
+            let take = available.min(n);
+            let byte = self.bytes[self.position / 8] as u64;
+            let bits = (byte >> (available - take)) & ((1 << take) - 1);
+
+            result = result.checked_shl(take as u32).unwrap_or(0) | bits;
+            self.position += take;
+            n -= take;
+        }
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_bits() {
+        let bits = Bits::from_hex("D2FE28").unwrap();
+        let mut reader = bits.reader();
+
+        assert_eq!(reader.read_bits(3), Some(6));
+        assert_eq!(reader.read_bits(3), Some(4));
+        assert_eq!(reader.read_bits(5), Some(0b10111));
+        assert_eq!(reader.read_bits(10), Some(0b1111000101));
+        assert_eq!(reader.read_bits(4), None);
+        assert_eq!(reader.read_bits(3), Some(0));
+        assert_eq!(reader.remaining(), 0);
+
+        let bits = Bits::from_hex("0123456789ABCDEF0F").unwrap();
+        let mut reader = bits.reader();
+        assert_eq!(reader.read_bits(64), Some(0x0123456789ABCDEF));
+        assert_eq!(reader.read_bits(8), Some(0x0F));
+    }
+
+    #[test]
+    fn test_push() {
+        let mut bits = Bits::new();
+        bits.push(6, 3);
+        bits.push(4, 3);
+        bits.push(0b101111111000101, 15);
+        assert_eq!(bits.len(), 21);
+        assert_eq!(bits.to_hex(), "D2FE28");
+
+        let mut joined = Bits::from_hex("D").unwrap();
+        joined.extend(&Bits::from_hex("2FE28").unwrap());
+        assert_eq!(joined, Bits::from_hex("D2FE28").unwrap());
+    }
+}
@@ -0,0 +1,200 @@
+use super::{Bits, Expr};
+use std::fmt;
+
+// How operators give the size of their children
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthType {
+    // Type 0: total number of bits, in 15 bits
+    Bits,
+    // Type 1: number of children, in 11 bits
+    Count,
+    // The shortest one that fits
+    Auto,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    // Version or id do not fit in 3 bits
+    Header { version: u8, id: u8 },
+    // Children do not fit in the length field, given as (bits, count)
+    Length { bits: usize, count: usize },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Header { version, id } => {
+                write!(
+                    f,
+                    "header (version={}, id={}) is too large",
+                    version, id
+                )
+            }
+            Self::Length { bits, count } => write!(
+                f,
+                "operator with {} children ({} bits) is too large",
+                count, bits
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+const BITS_WIDTH: usize = 15;
+const COUNT_WIDTH: usize = 11;
+const GROUP_WIDTH: usize = 4;
+
+pub fn encode(expr: &Expr, length: LengthType) -> Result<Bits, EncodeError> {
+    let mut result = Bits::new();
+    encode_into(&mut result, expr, length)?;
+    Ok(result)
+}
+
+fn encode_into(
+    bits: &mut Bits,
+    expr: &Expr,
+    length: LengthType,
+) -> Result<(), EncodeError> {
+    let (version, id) = match expr {
+        Expr::Literal { version, .. } => (*version, Expr::LITERAL_ID),
+        Expr::Operator { version, id, .. } => (*version, *id),
+    };
+    if version > 7
+        || id > 7
+        || (id == Expr::LITERAL_ID) != matches!(expr, Expr::Literal { .. })
+    {
+        return Err(EncodeError::Header { version, id });
+    }
+    bits.push(version as u64, 3);
+    bits.push(id as u64, 3);
+
+    match expr {
+        // Groups of 4 bits, each one with a leading bit set but the last
+        Expr::Literal { value, .. } => {
+            let nibbles = value.to_radix_be(1 << GROUP_WIDTH);
+            for (i, nibble) in nibbles.iter().enumerate() {
+                bits.push((i + 1 < nibbles.len()) as u64, 1);
+                bits.push(*nibble as u64, GROUP_WIDTH);
+            }
+        }
+        Expr::Operator { children, .. } => {
+            let mut contents = Bits::new();
+            for child in children {
+                encode_into(&mut contents, child, length)?;
+            }
+
+            let count = children.len();
+            let fits_bits = contents.len() < 1 << BITS_WIDTH;
+            let fits_count = count < 1 << COUNT_WIDTH;
+            let error = EncodeError::Length {
+                bits: contents.len(),
+                count,
+            };
+
+            match length {
+                LengthType::Count | LengthType::Auto if fits_count => {
+                    bits.push(1, 1);
+                    bits.push(count as u64, COUNT_WIDTH);
+                }
+                LengthType::Bits | LengthType::Auto if fits_bits => {
+                    bits.push(0, 1);
+                    bits.push(contents.len() as u64, BITS_WIDTH);
+                }
+                _ => return Err(error),
+            }
+            bits.extend(&contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::PacketFactory;
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn random_expr(rng: &mut StdRng, depth: usize) -> Expr {
+        let version = rng.gen_range(0..8);
+        if depth == 0 || rng.gen_bool(0.3) {
+            // Values of any size, including 0 and more than 64 bits
+            let bits = rng.gen_range(0..=128);
+            let value = rng.gen::<u128>().checked_shr(128 - bits).unwrap_or(0);
+            return Expr::literal(version, value);
+        }
+
+        let id = [0, 1, 2, 3, 5, 6, 7][rng.gen_range(0..7)];
+        let count = match id {
+            5..=7 => 2,
+            _ => rng.gen_range(1..=4),
+        };
+        Expr::Operator {
+            version,
+            id,
+            children: (0..count).map(|_| random_expr(rng, depth - 1)).collect(),
+        }
+    }
+
+    fn literal_zero() -> Expr {
+        Expr::literal(0, 0u32)
+    }
+
+    #[test]
+    fn test_examples() {
+        let literal = Expr::literal(6, 2021u32);
+        let bits = encode(&literal, LengthType::Auto).unwrap();
+        assert_eq!(bits.to_hex(), "D2FE28");
+
+        // 0x38006F45291200 encodes the same packet with length type 0
+        let operator = Expr::Operator {
+            version: 1,
+            id: 6,
+            children: vec![Expr::literal(6, 10u32), Expr::literal(2, 20u32)],
+        };
+        let bits = encode(&operator, LengthType::Bits).unwrap();
+        assert_eq!(bits.to_hex(), "38006F45291200");
+        let bits = encode(&operator, LengthType::Count).unwrap();
+        assert_eq!(bits.len(), 6 + 1 + 11 + 11 + 16);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = StdRng::seed_from_u64(16);
+
+        for _ in 0..200 {
+            let expr = random_expr(&mut rng, 4);
+            for length in
+                [LengthType::Bits, LengthType::Count, LengthType::Auto]
+            {
+                let bits = encode(&expr, length).unwrap();
+                let packet = PacketFactory::factory(bits.reader()).unwrap();
+                assert_eq!(packet.len(), bits.len());
+                assert_eq!(Expr::from_packet(packet.as_ref()), expr);
+
+                let bits = Bits::from_hex(&bits.to_hex()).unwrap();
+                let packet = PacketFactory::factory(bits.reader()).unwrap();
+                assert_eq!(Expr::from_packet(packet.as_ref()), expr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let literal = Expr::literal(8, 1u32);
+        assert!(matches!(
+            encode(&literal, LengthType::Auto),
+            Err(EncodeError::Header { .. })
+        ));
+
+        let children = vec![literal_zero(); 1 << COUNT_WIDTH];
+        let operator = Expr::Operator {
+            version: 0,
+            id: 0,
+            children,
+        };
+        assert!(encode(&operator, LengthType::Count).is_err());
+        assert!(encode(&operator, LengthType::Auto).is_ok());
+    }
+}
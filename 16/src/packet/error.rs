@@ -0,0 +1,59 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeReason {
+    // The transmission ended before the `needed` bits
+    Truncated { needed: usize },
+    // Children of a length type 0 operator took more bits than stated
+    Overrun { expected: usize, found: usize },
+    // Operator with a number of children it can not be computed with
+    Arity { id: u8, children: usize },
+    // Packet nested deeper than the decoder allows
+    Depth { limit: usize },
+    // Bits after the outermost packet are not all zeros
+    Padding,
+}
+
+// Reason of the failure, and where it happened: a bit offset from the start
+// of the transmission and the path of child indices to the packet that was
+// being decoded (empty for the outermost one)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    pub offset: usize,
+    pub path: Vec<usize>,
+    pub reason: DecodeReason,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "bit {} (packet ", self.offset)?;
+        match self.path.is_empty() {
+            true => write!(f, "root")?,
+            false => {
+                let path = self.path.iter().map(|i| i.to_string());
+                write!(f, "root.{}", path.collect::<Vec<_>>().join("."))?
+            }
+        }
+        write!(f, "): ")?;
+
+        match &self.reason {
+            DecodeReason::Truncated { needed } => {
+                write!(f, "transmission ends before {} more bits", needed)
+            }
+            DecodeReason::Overrun { expected, found } => write!(
+                f,
+                "children take {} bits instead of {}",
+                found, expected
+            ),
+            DecodeReason::Arity { id, children } => {
+                write!(f, "operator {} with {} children", id, children)
+            }
+            DecodeReason::Depth { limit } => {
+                write!(f, "packets nested more than {} deep", limit)
+            }
+            DecodeReason::Padding => write!(f, "padding is not zeros"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
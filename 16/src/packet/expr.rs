@@ -0,0 +1,48 @@
+use super::Packet;
+use num::BigUint;
+
+// Owned packet tree, which can be built by hand (e.g. to be encoded) or from
+// a decoded packet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Literal {
+        version: u8,
+        value: BigUint,
+    },
+    Operator {
+        version: u8,
+        id: u8,
+        children: Vec<Expr>,
+    },
+}
+
+impl Expr {
+    pub const LITERAL_ID: u8 = 4;
+
+    #[allow(dead_code)]
+    pub fn literal<V: Into<BigUint>>(version: u8, value: V) -> Self {
+        Self::Literal {
+            version,
+            value: value.into(),
+        }
+    }
+
+    pub fn from_packet(packet: &dyn Packet) -> Self {
+        match packet.id() {
+            Self::LITERAL_ID => Self::Literal {
+                version: packet.version(),
+                value: BigUint::from_radix_be(packet.literal().unwrap(), 16)
+                    .unwrap(),
+            },
+            id => Self::Operator {
+                version: packet.version(),
+                id,
+                children: packet
+                    .children()
+                    .iter()
+                    .map(|c| Self::from_packet(c.as_ref()))
+                    .collect(),
+            },
+        }
+    }
+}
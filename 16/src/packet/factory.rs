@@ -1,16 +1,178 @@
 use super::*;
 
-pub fn factory(raw: &[bool]) -> Box<dyn Packet> {
-    let header = PacketHeader::init(raw);
-
-    match header.id {
-        4 => Box::new(PacketLiteral::init(
-            header,
-            &raw[PacketHeader::VTABLE_END..],
-        )),
-        _ => Box::new(PacketOperator::init(
-            header,
-            &raw[PacketHeader::VTABLE_END..],
-        )),
+// Deepest nesting of packets, so that walking packet trees recursively can
+// not overflow the stack
+pub const MAX_DEPTH: usize = 512;
+
+// Decodes the outermost packet, which can only be followed by zeros
+pub fn factory(mut reader: BitReader) -> Result<Box<dyn Packet>, DecodeError> {
+    let packet = decode(&mut reader, &mut Vec::new())?;
+
+    // Padding is read in chunks, so the offset of the first one is searched
+    // within the chunk
+    while reader.remaining() > 0 {
+        let offset = reader.position();
+        let width = reader.remaining().min(64);
+        let chunk = reader.read_bits(width).unwrap();
+        if chunk != 0 {
+            return Err(DecodeError {
+                offset: offset + width - (64 - chunk.leading_zeros()) as usize,
+                path: Vec::new(),
+                reason: DecodeReason::Padding,
+            });
+        }
+    }
+
+    Ok(packet)
+}
+
+// Decodes the packet at the reader position, found at `path`. The path is
+// extended while decoding children, and restored afterwards
+pub fn decode(
+    reader: &mut BitReader,
+    path: &mut Vec<usize>,
+) -> Result<Box<dyn Packet>, DecodeError> {
+    if path.len() > MAX_DEPTH {
+        return Err(DecodeError {
+            offset: reader.position(),
+            path: path.clone(),
+            reason: DecodeReason::Depth { limit: MAX_DEPTH },
+        });
+    }
+
+    let header = PacketHeader::init(reader, path)?;
+
+    Ok(match header.id {
+        4 => Box::new(PacketLiteral::init(header, reader, path)?),
+        _ => Box::new(PacketOperator::init(header, reader, path)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(hex: &str) -> DecodeError {
+        factory(Bits::from_hex(hex).unwrap().reader())
+            .err()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_truncated() {
+        assert_eq!(
+            error("D2FE"),
+            DecodeError {
+                offset: 16,
+                path: vec![],
+                reason: DecodeReason::Truncated { needed: 5 },
+            }
+        );
+
+        // Second literal of an operator, in the middle of its first group
+        let bits = Bits::from_hex("38006F45291200").unwrap();
+        assert_eq!(
+            factory(BitReader::new(bits.bytes(), 40)).err().unwrap(),
+            DecodeError {
+                offset: 39,
+                path: vec![1],
+                reason: DecodeReason::Truncated { needed: 4 },
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(
+            error("D2FE29"),
+            DecodeError {
+                offset: 23,
+                path: vec![],
+                reason: DecodeReason::Padding,
+            }
+        );
+
+        // Same as 38006F45291200, with children taking 27 bits while the
+        // operator says 20
+        assert_eq!(
+            error("38005345291200"),
+            DecodeError {
+                offset: 42,
+                path: vec![],
+                reason: DecodeReason::Overrun {
+                    expected: 20,
+                    found: 27
+                },
+            }
+        );
+
+        let literal = Expr::literal(0, 1u32);
+        let nested = Expr::Operator {
+            version: 0,
+            id: 0,
+            children: vec![
+                literal.clone(),
+                Expr::Operator {
+                    version: 0,
+                    id: 5,
+                    children: vec![literal; 3],
+                },
+            ],
+        };
+        let hex = encode(&nested, LengthType::Auto).unwrap().to_hex();
+        assert_eq!(
+            error(&hex),
+            DecodeError {
+                offset: 18 + 11,
+                path: vec![1],
+                reason: DecodeReason::Arity { id: 5, children: 3 },
+            }
+        );
+        assert!(error(&hex)
+            .to_string()
+            .starts_with("bit 29 (packet root.1)"));
+    }
+
+    // Sums of a single child, nested `depth` times around the literal 7
+    fn nested(depth: usize) -> Bits {
+        let mut bits = Bits::new();
+        for _ in 0..depth {
+            bits.push(0, 6);
+            bits.push(1, 1);
+            bits.push(1, 11);
+        }
+        bits.push(4, 6);
+        bits.push(7, 5);
+        bits
+    }
+
+    #[test]
+    fn test_depth() {
+        let bits = nested(MAX_DEPTH);
+        let packet = factory(bits.reader()).unwrap();
+        let packet = packet.as_ref();
+
+        assert_eq!(packet.all_versions().len(), MAX_DEPTH + 1);
+        assert_eq!(evaluate::<u64>(packet), Ok(7));
+        assert_eq!(render::infix(packet), "7");
+        assert!(render::sexpr(packet).starts_with("(sum (sum "));
+        assert_eq!(render::tree(packet).lines().count(), MAX_DEPTH + 1);
+        assert_eq!(
+            encode(&Expr::from_packet(packet), LengthType::Count).unwrap(),
+            bits
+        );
+
+        // Deeper transmissions fail at the first packet too deep, however
+        // deep they are
+        for depth in [MAX_DEPTH + 1, 20_000] {
+            assert_eq!(
+                error(&nested(depth).to_hex()),
+                DecodeError {
+                    offset: 18 * (MAX_DEPTH + 1),
+                    path: vec![0; MAX_DEPTH + 1],
+                    reason: DecodeReason::Depth { limit: MAX_DEPTH },
+                }
+            );
+        }
     }
 }
@@ -1,21 +1,22 @@
+use super::{BitReader, DecodeError};
+
 pub struct Header {
     pub version: u8,
     pub id: u8,
 }
 
 impl Header {
-    const VTABLE_VER: (usize, usize) = (0, 3);
-    const VTABLE_UID: (usize, usize) = (3, 6);
-    pub const VTABLE_END: usize = Self::VTABLE_UID.1;
+    const VERSION_BITS: usize = 3;
+    const UID_BITS: usize = 3;
+    pub const VTABLE_END: usize = Self::VERSION_BITS + Self::UID_BITS;
 
-    pub fn init(data: &[bool]) -> Self {
-        Self {
-            version: super::parse_bitstream(
-                &data[Self::VTABLE_VER.0..Self::VTABLE_VER.1],
-            ),
-            id: super::parse_bitstream(
-                &data[Self::VTABLE_UID.0..Self::VTABLE_UID.1],
-            ),
-        }
+    pub fn init(
+        reader: &mut BitReader,
+        path: &[usize],
+    ) -> Result<Self, DecodeError> {
+        Ok(Self {
+            version: super::read(reader, Self::VERSION_BITS, path)? as u8,
+            id: super::read(reader, Self::UID_BITS, path)? as u8,
+        })
     }
 }
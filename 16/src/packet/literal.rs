@@ -1,50 +1,53 @@
 use super::Packet;
 use super::PacketHeader;
+use super::{BitReader, DecodeError};
 
 pub struct Literal {
     header: PacketHeader,
     length: usize,
-    value: u64,
+    nibbles: Vec<u8>,
 }
 
 impl Literal {
     const GROUP_LENGTH: usize = 5;
 
     #[allow(dead_code)]
-    pub fn parse(raw: &[bool]) -> Self {
-        Self::init(PacketHeader::init(raw), &raw[PacketHeader::VTABLE_END..])
+    pub fn parse(reader: &mut BitReader) -> Result<Self, DecodeError> {
+        Self::init(PacketHeader::init(reader, &[])?, reader, &[])
     }
 
-    pub fn init(header: PacketHeader, contents: &[bool]) -> Self {
+    // The reader is right after the header
+    pub fn init(
+        header: PacketHeader,
+        reader: &mut BitReader,
+        path: &[usize],
+    ) -> Result<Self, DecodeError> {
         debug_assert!(header.id == 4);
 
-        let mut count = 0;
-        let mut data = Vec::new();
+        // Digits are kept as read, so values can be as large as needed
+        let mut nibbles = Vec::new();
         // Extract one group of Self::GROUP_LENGTH bytes in sequence
         // First bit encodes if this is the first or last group
         // Remaining four bits code what value is represented by the packet
         // literal
         // Parsing stops when the first bit encodes the "last" group
         loop {
-            let bitselect = (count * Self::GROUP_LENGTH + 1)
-                ..((count + 1) * Self::GROUP_LENGTH);
+            let group = super::read(reader, Self::GROUP_LENGTH, path)?;
+            nibbles.push((group & 0xF) as u8);
 
-            data.extend_from_slice(&contents[bitselect]);
-
-            if contents[count * Self::GROUP_LENGTH] {
-                count += 1;
-            } else {
+            if group >> (Self::GROUP_LENGTH - 1) == 0 {
                 break;
             }
         }
 
         // Note: Packet length is the some of the length of the packet header
         // plus all the bits we decoded in order to extract 'value
-        Self {
+        Ok(Self {
             header,
-            value: super::parse_bitstream(&data),
-            length: PacketHeader::VTABLE_END + (count + 1) * Self::GROUP_LENGTH,
-        }
+            length: PacketHeader::VTABLE_END
+                + nibbles.len() * Self::GROUP_LENGTH,
+            nibbles,
+        })
     }
 }
 
@@ -65,7 +68,7 @@ impl Packet for Literal {
         self.length
     }
 
-    fn compute(&self) -> u64 {
-        self.value
+    fn literal(&self) -> Option<&[u8]> {
+        Some(&self.nibbles)
     }
 }
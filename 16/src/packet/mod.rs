@@ -1,25 +1,37 @@
+mod bits;
+mod encoder;
+mod error;
+mod expr;
 pub mod factory;
 mod header;
 mod literal;
 mod operator;
+pub mod render;
 mod traits;
+mod value;
 
+pub use bits::{BitReader, Bits};
+pub use encoder::{encode, LengthType};
+pub use error::{DecodeError, DecodeReason};
+pub use expr::Expr;
 pub use factory as PacketFactory;
 pub use header::Header as PacketHeader;
 pub use literal::Literal as PacketLiteral;
 pub use operator::Operator as PacketOperator;
 pub use traits::Packet;
+pub use value::{evaluate, EvalError};
 
-fn parse_bitstream<T>(data: &[bool]) -> T
-where
-    T: From<bool>
-        + std::ops::Shl<usize, Output = T>
-        + std::ops::BitOr<Output = T>,
-{
-    data.into_iter()
-        .rev()
-        .enumerate()
-        .map(|(i, d)| T::from(*d) << i)
-        .reduce(|acc, e| acc.bitor(e))
-        .unwrap()
+// Next `n` bits, failing if the transmission is shorter
+fn read(
+    reader: &mut BitReader,
+    n: usize,
+    path: &[usize],
+) -> Result<u64, DecodeError> {
+    reader.read_bits(n).ok_or_else(|| DecodeError {
+        offset: reader.position(),
+        path: path.to_vec(),
+        reason: DecodeReason::Truncated {
+            needed: n - reader.remaining(),
+        },
+    })
 }
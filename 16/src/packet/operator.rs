@@ -1,6 +1,7 @@
 use super::Packet;
 use super::PacketFactory;
 use super::PacketHeader;
+use super::{BitReader, DecodeError, DecodeReason};
 
 struct OperatorLength {
     id: bool,
@@ -15,35 +16,60 @@ pub struct Operator<'a> {
 
 impl Operator<'_> {
     #[allow(dead_code)]
-    pub fn parse(raw: &[bool]) -> Self {
-        Self::init(PacketHeader::init(raw), &raw[PacketHeader::VTABLE_END..])
+    pub fn parse(reader: &mut BitReader) -> Result<Self, DecodeError> {
+        Self::init(PacketHeader::init(reader, &[])?, reader, &mut Vec::new())
     }
 
-    pub fn init(header: PacketHeader, contents: &[bool]) -> Self {
+    // The reader is right after the header
+    pub fn init(
+        header: PacketHeader,
+        reader: &mut BitReader,
+        path: &mut Vec<usize>,
+    ) -> Result<Self, DecodeError> {
         debug_assert!(header.id != 4);
 
-        let bitsize: u8 = if contents[0] { 11 } else { 15 };
+        let offset = reader.position() - PacketHeader::VTABLE_END;
+        let id = super::read(reader, 1, path)? == 1;
+        let bitsize: u8 = if id { 11 } else { 15 };
         let length = OperatorLength {
-            id: contents[0],
+            id,
             bitsize,
-            value: super::parse_bitstream(&contents[1..=bitsize as usize]),
+            value: super::read(reader, bitsize as usize, path)? as u16,
         };
 
-        Self {
-            children: Self::parse_as_children(
-                &length,
-                &contents[(bitsize as usize + 1)..],
-            ),
+        let children = Self::parse_as_children(&length, reader, path)?;
+
+        // Comparisons need two values, and minimum/maximum at least one
+        let valid = match header.id {
+            2 | 3 => !children.is_empty(),
+            5..=7 => children.len() == 2,
+            _ => true,
+        };
+        if !valid {
+            return Err(DecodeError {
+                offset,
+                path: path.to_vec(),
+                reason: DecodeReason::Arity {
+                    id: header.id,
+                    children: children.len(),
+                },
+            });
+        }
+
+        Ok(Self {
+            children,
             header,
             length,
-        }
+        })
     }
 
     fn parse_as_children<'a>(
         length: &OperatorLength,
-        contents: &[bool],
-    ) -> Vec<Box<dyn Packet + 'a>> {
+        reader: &mut BitReader,
+        path: &mut Vec<usize>,
+    ) -> Result<Vec<Box<dyn Packet + 'a>>, DecodeError> {
         let mut result: Vec<Box<dyn Packet + 'a>> = Vec::new();
+        let start = reader.position();
         let mut cursor = 0usize;
 
         loop {
@@ -57,13 +83,27 @@ impl Operator<'_> {
 
             // Create a new packet. This depends on the header. Logic was passed
             // down to the PacketFactory module
-            result.push(PacketFactory::factory(&contents[cursor..]));
+            path.push(result.len());
+            result.push(PacketFactory::decode(reader, path)?);
+            path.pop();
 
-            // Update cursor with the last added packet size
-            cursor += result.last().unwrap().len();
+            // Update cursor with the bits read by the last added packet
+            cursor = reader.position() - start;
         }
 
-        result
+        // The last child can not go past the stated number of bits
+        if !length.id && cursor > length.value as usize {
+            return Err(DecodeError {
+                offset: start + length.value as usize,
+                path: path.to_vec(),
+                reason: DecodeReason::Overrun {
+                    expected: length.value as usize,
+                    found: cursor,
+                },
+            });
+        }
+
+        Ok(result)
     }
 }
 
@@ -87,26 +127,7 @@ impl Packet for Operator<'_> {
         + self.children.iter().map(|c| c.len()).sum::<usize>() // from children
     }
 
-    fn compute(&self) -> u64 {
-        let values = &self.children;
-        match self.id() {
-            0 => values.iter().map(|c| c.compute()).sum(),
-            1 => values.iter().map(|c| c.compute()).product(),
-            2 => values.iter().map(|c| c.compute()).min().unwrap(),
-            3 => values.iter().map(|c| c.compute()).max().unwrap(),
-            5 => {
-                debug_assert!(values.len() == 2);
-                (values[0].compute() > values[1].compute()) as u64
-            }
-            6 => {
-                debug_assert!(values.len() == 2);
-                (values[0].compute() < values[1].compute()) as u64
-            }
-            7 => {
-                debug_assert!(values.len() == 2);
-                (values[0].compute() == values[1].compute()) as u64
-            }
-            _ => panic!("Operation not implemented!"),
-        }
+    fn literal(&self) -> Option<&[u8]> {
+        None
     }
 }
@@ -0,0 +1,222 @@
+use super::{Expr, Packet};
+use num::BigUint;
+
+const LITERAL_ID: u8 = Expr::LITERAL_ID;
+
+// Literals are printed whatever their size
+fn literal(packet: &dyn Packet) -> String {
+    BigUint::from_radix_be(packet.literal().unwrap_or_default(), 16)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn name(id: u8) -> String {
+    match id {
+        0 => "sum".to_owned(),
+        1 => "product".to_owned(),
+        2 => "min".to_owned(),
+        3 => "max".to_owned(),
+        5 => "gt".to_owned(),
+        6 => "lt".to_owned(),
+        7 => "eq".to_owned(),
+        id => format!("op{}", id),
+    }
+}
+
+// One packet per line, indented by depth, with its version
+pub fn tree(packet: &dyn Packet) -> String {
+    fn visit(packet: &dyn Packet, depth: usize, result: &mut String) {
+        let label = match packet.id() {
+            LITERAL_ID => literal(packet),
+            id => name(id),
+        };
+        result.push_str(&format!(
+            "{}{} (v{})\n",
+            "  ".repeat(depth),
+            label,
+            packet.version()
+        ));
+
+        for child in packet.children() {
+            visit(child.as_ref(), depth + 1, result);
+        }
+    }
+
+    let mut result = String::new();
+    visit(packet, 0, &mut result);
+    result
+}
+
+// e.g. `(sum 1 (product 2 3))`
+pub fn sexpr(packet: &dyn Packet) -> String {
+    fn visit(packet: &dyn Packet, result: &mut String) {
+        if packet.id() == LITERAL_ID {
+            result.push_str(&literal(packet));
+            return;
+        }
+
+        result.push('(');
+        result.push_str(&name(packet.id()));
+        for child in packet.children() {
+            result.push(' ');
+            visit(child.as_ref(), result);
+        }
+        result.push(')');
+    }
+
+    let mut result = String::new();
+    visit(packet, &mut result);
+    result
+}
+
+// Binding strength of each kind of expression, from loosest to tightest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Comparison,
+    Sum,
+    Product,
+    Atom,
+}
+
+// e.g. `1 + 2*3`, with parentheses only where needed. Functions are used for
+// the operators without a symbol
+pub fn infix(packet: &dyn Packet) -> String {
+    let mut result = String::new();
+    write_infix(packet, &mut result, Precedence::Comparison, false);
+    result
+}
+
+// Each packet wraps itself in parentheses when it binds looser than the
+// operator around it (or as loose, if that operator is `strict`)
+fn write_infix(
+    packet: &dyn Packet,
+    result: &mut String,
+    outer: Precedence,
+    strict: bool,
+) {
+    let children = packet.children();
+    let precedence = match (packet.id(), children.len()) {
+        (LITERAL_ID, _) | (0 | 1, 0) => Precedence::Atom,
+        // Sums and products of a single value are written as that value
+        (0 | 1, 1) => {
+            return write_infix(children[0].as_ref(), result, outer, strict)
+        }
+        (0, _) => Precedence::Sum,
+        (1, _) => Precedence::Product,
+        (5..=7, 2) => Precedence::Comparison,
+        _ => Precedence::Atom,
+    };
+
+    let operands = |result: &mut String, separator, outer, strict| {
+        for (index, child) in children.iter().enumerate() {
+            if index > 0 {
+                result.push_str(separator);
+            }
+            write_infix(child.as_ref(), result, outer, strict);
+        }
+    };
+
+    let wrap = precedence < outer || (strict && precedence == outer);
+    if wrap {
+        result.push('(');
+    }
+    match (packet.id(), children.len()) {
+        (LITERAL_ID, _) => result.push_str(&literal(packet)),
+        (0, 0) => result.push('0'),
+        (1, 0) => result.push('1'),
+        (0, _) => operands(result, " + ", Precedence::Sum, false),
+        (1, _) => operands(result, "*", Precedence::Product, false),
+        (id @ 5..=7, 2) => {
+            let symbol = [" > ", " < ", " == "][id as usize - 5];
+            operands(result, symbol, Precedence::Comparison, true)
+        }
+        (id, _) => {
+            result.push_str(&name(id));
+            result.push('(');
+            operands(result, ", ", Precedence::Comparison, false);
+            result.push(')');
+        }
+    }
+    if wrap {
+        result.push(')');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Bits, PacketFactory};
+    use super::*;
+
+    fn render(hex: &str) -> (String, String, String) {
+        let packet =
+            PacketFactory::factory(Bits::from_hex(hex).unwrap().reader())
+                .unwrap();
+        (
+            tree(packet.as_ref()),
+            sexpr(packet.as_ref()),
+            infix(packet.as_ref()),
+        )
+    }
+
+    #[test]
+    fn test_render() {
+        let (tree, sexpr, infix) = render("9C0141080250320F1802104A08");
+        assert_eq!(sexpr, "(eq (sum 1 3) (product 2 2))");
+        assert_eq!(infix, "1 + 3 == 2*2");
+        assert_eq!(
+            tree,
+            "eq (v4)\n  sum (v2)\n    1 (v2)\n    3 (v4)\n  \
+             product (v6)\n    2 (v0)\n    2 (v2)\n"
+        );
+
+        let (_, sexpr, infix) = render("9C005AC2F8F0");
+        assert_eq!(sexpr, "(eq 5 15)");
+        assert_eq!(infix, "5 == 15");
+
+        let (_, sexpr, infix) = render("880086C3E88112");
+        assert_eq!(sexpr, "(min 7 8 9)");
+        assert_eq!(infix, "min(7, 8, 9)");
+    }
+
+    #[test]
+    fn test_infix_parentheses() {
+        use super::super::{encode, LengthType};
+
+        let literal = |value: u32| Expr::literal(0, value);
+        let operator = |id, children| Expr::Operator {
+            version: 0,
+            id,
+            children,
+        };
+
+        // (1 + 2) * 3 > (4 > 5)
+        let expr = operator(
+            5,
+            vec![
+                operator(
+                    1,
+                    vec![operator(0, vec![literal(1), literal(2)]), literal(3)],
+                ),
+                operator(5, vec![literal(4), literal(5)]),
+            ],
+        );
+        let bits = encode(&expr, LengthType::Auto).unwrap();
+        let packet = PacketFactory::factory(bits.reader()).unwrap();
+
+        assert_eq!(infix(packet.as_ref()), "(1 + 2)*3 > (4 > 5)");
+        assert_eq!(
+            sexpr(packet.as_ref()),
+            "(gt (product (sum 1 2) 3) (gt 4 5))"
+        );
+
+        // Single value products take the precedence of what they wrap
+        let sum = operator(0, vec![literal(1), literal(2)]);
+        let expr = operator(
+            1,
+            vec![operator(1, vec![operator(0, vec![sum])]), literal(3)],
+        );
+        let bits = encode(&expr, LengthType::Auto).unwrap();
+        let packet = PacketFactory::factory(bits.reader()).unwrap();
+        assert_eq!(infix(packet.as_ref()), "(1 + 2)*3");
+    }
+}
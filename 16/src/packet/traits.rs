@@ -1,19 +1,23 @@
 pub trait Packet {
     fn version(&self) -> u8;
     fn id(&self) -> u8;
-    fn compute(&self) -> u64;
+    // Value of literals, as big endian hexadecimal digits
+    fn literal(&self) -> Option<&[u8]>;
+    // Number of bits of the packet, including its children
+    #[allow(dead_code)]
     fn len(&self) -> usize;
     fn children<'a>(&'a self) -> &'a [Box<dyn Packet + 'a>];
 
+    // Versions in depth first order, walked with an explicit stack
     fn all_versions(&self) -> Vec<u8> {
-        [
-            vec![self.version()],
-            self.children()
-                .iter()
-                .map(|c| c.all_versions())
-                .reduce(|acc, e| [acc, e].concat())
-                .unwrap_or_default(),
-        ]
-        .concat()
+        let mut result = vec![self.version()];
+        let mut stack = self.children().iter().rev().collect::<Vec<_>>();
+
+        while let Some(packet) = stack.pop() {
+            result.push(packet.version());
+            stack.extend(packet.children().iter().rev());
+        }
+
+        result
     }
 }
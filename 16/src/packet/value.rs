@@ -0,0 +1,165 @@
+use super::Packet;
+use num::{CheckedAdd, CheckedMul, FromPrimitive, One, Zero};
+use std::fmt;
+
+// Numeric backend used to evaluate packets (e.g. u64, u128 or BigUint)
+pub trait Value:
+    Clone + Ord + Zero + One + CheckedAdd + CheckedMul + FromPrimitive
+{
+    // Big endian hexadecimal digits, or nothing if they do not fit
+    fn from_nibbles(nibbles: &[u8]) -> Option<Self> {
+        let radix = Self::from_u8(16)?;
+        nibbles.iter().try_fold(Self::zero(), |acc, n| {
+            acc.checked_mul(&radix)?.checked_add(&Self::from_u8(*n)?)
+        })
+    }
+}
+
+impl<T> Value for T where
+    T: Clone + Ord + Zero + One + CheckedAdd + CheckedMul + FromPrimitive
+{
+}
+
+// Path of child indices to the packet that could not be evaluated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    Overflow { path: Vec<usize> },
+    Arity { path: Vec<usize>, id: u8 },
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (path, reason) = match self {
+            Self::Overflow { path } => (path, "value overflows".to_owned()),
+            Self::Arity { path, id } => {
+                (path, format!("operator {} with wrong arity", id))
+            }
+        };
+
+        let path = path.iter().map(|i| format!(".{}", i)).collect::<String>();
+        write!(f, "packet root{}: {}", path, reason)
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+pub fn evaluate<V: Value>(packet: &dyn Packet) -> Result<V, EvalError> {
+    evaluate_at(packet, &mut Vec::new())
+}
+
+fn evaluate_at<V: Value>(
+    packet: &dyn Packet,
+    path: &mut Vec<usize>,
+) -> Result<V, EvalError> {
+    let overflow =
+        |path: &Vec<usize>| EvalError::Overflow { path: path.clone() };
+
+    if let Some(nibbles) = packet.literal() {
+        return V::from_nibbles(nibbles).ok_or_else(|| overflow(path));
+    }
+
+    let mut values = Vec::with_capacity(packet.children().len());
+    for (i, child) in packet.children().iter().enumerate() {
+        path.push(i);
+        values.push(evaluate_at::<V>(child.as_ref(), path)?);
+        path.pop();
+    }
+
+    let flag = |b: bool| if b { V::one() } else { V::zero() };
+    let result = match (packet.id(), values.as_slice()) {
+        (0, _) => values
+            .iter()
+            .try_fold(V::zero(), |acc, v| acc.checked_add(v)),
+        (1, _) => values
+            .iter()
+            .try_fold(V::one(), |acc, v| acc.checked_mul(v)),
+        (2, [_, ..]) => values.into_iter().min(),
+        (3, [_, ..]) => values.into_iter().max(),
+        (5, [a, b]) => Some(flag(a > b)),
+        (6, [a, b]) => Some(flag(a < b)),
+        (7, [a, b]) => Some(flag(a == b)),
+        (id, _) => {
+            return Err(EvalError::Arity {
+                path: path.clone(),
+                id,
+            })
+        }
+    };
+
+    result.ok_or_else(|| overflow(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{encode, Bits, Expr, LengthType, PacketFactory};
+    use super::*;
+    use num::BigUint;
+
+    fn evaluate_hex<V: Value>(hex: &str) -> Result<V, EvalError> {
+        let packet =
+            PacketFactory::factory(Bits::from_hex(hex).unwrap().reader());
+        evaluate(packet.unwrap().as_ref())
+    }
+
+    fn evaluate_expr<V: Value>(expr: &Expr) -> Result<V, EvalError> {
+        let bits = encode(expr, LengthType::Auto).unwrap();
+        let packet = PacketFactory::factory(bits.reader()).unwrap();
+        evaluate(packet.as_ref())
+    }
+
+    #[test]
+    fn test_examples() {
+        let examples = [
+            ("C200B40A82", 3),
+            ("04005AC33890", 54),
+            ("880086C3E88112", 7),
+            ("CE00C43D881120", 9),
+            ("D8005AC2A8F0", 1),
+            ("F600BC2D8F", 0),
+            ("9C005AC2F8F0", 0),
+            ("9C0141080250320F1802104A08", 1),
+        ];
+
+        for (hex, value) in examples {
+            assert_eq!(evaluate_hex::<u64>(hex), Ok(value));
+            assert_eq!(evaluate_hex::<BigUint>(hex), Ok(BigUint::from(value)));
+        }
+    }
+
+    #[test]
+    fn test_overflow() {
+        // 20 groups of 4 bits
+        let big = BigUint::from(u64::MAX) * 4096u32;
+        let literal = Expr::literal(0, big.clone());
+        assert_eq!(
+            evaluate_expr::<u64>(&literal),
+            Err(EvalError::Overflow { path: vec![] })
+        );
+        assert_eq!(
+            evaluate_expr::<u128>(&literal),
+            Ok(u64::MAX as u128 * 4096)
+        );
+        assert_eq!(evaluate_expr::<BigUint>(&literal), Ok(big));
+
+        let sum = Expr::Operator {
+            version: 0,
+            id: 0,
+            children: vec![
+                Expr::literal(0, 1u8),
+                Expr::Operator {
+                    version: 0,
+                    id: 1,
+                    children: vec![
+                        Expr::literal(0, u64::MAX),
+                        Expr::literal(0, 2u8),
+                    ],
+                },
+            ],
+        };
+        assert_eq!(
+            evaluate_expr::<u64>(&sum),
+            Err(EvalError::Overflow { path: vec![1] })
+        );
+        assert_eq!(evaluate_expr::<u128>(&sum), Ok(u64::MAX as u128 * 2 + 1));
+    }
+}
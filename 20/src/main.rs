@@ -1,3 +1,5 @@
+use common::image::Rgb;
+use common::terminal::{grid_frame, Animation, Control};
 use grid::Grid;
 use itertools::Itertools;
 use num::Integer;
@@ -14,11 +16,16 @@ pub fn image_to_string(data: &Grid<bool>) -> String {
         .join("\n")
 }
 
-pub fn image_enchance(
+// `on_step` is called with the image after each enhancement
+pub fn image_enchance<F>(
     mut input: Grid<bool>,
     decoder: &[bool],
     times: usize,
-) -> Grid<bool> {
+    mut on_step: F,
+) -> Grid<bool>
+where
+    F: FnMut(usize, &Grid<bool>),
+{
     debug_assert!(times > 0);
 
     const HALF_SIZE_KERNEL: isize = 1;
@@ -69,6 +76,8 @@ pub fn image_enchance(
                 result[r as usize][c as usize] = decoder[value];
             }
         }
+
+        on_step(t + 1, &result);
     }
 
     result
@@ -106,6 +115,17 @@ pub fn parse(filepath: &str) -> (Vec<bool>, Grid<bool>) {
     (decoder, image)
 }
 
+pub fn image_frame(step: usize, data: &Grid<bool>) -> String {
+    format!(
+        "Enhancement {}\n{}",
+        step,
+        grid_frame(data, |v| match v {
+            true => ('#', Rgb(255, 230, 120)),
+            false => ('.', Rgb(70, 70, 90)),
+        })
+    )
+}
+
 fn main() {
     // Parse map filepath from first argument
     let filepath = std::env::args()
@@ -114,9 +134,18 @@ fn main() {
 
     let (decoder, image) = parse(&filepath);
 
-    let image1 = image_enchance(image.clone(), &decoder, 2);
+    let image1 = image_enchance(image.clone(), &decoder, 2, |_, _| ());
     println!("Problem #1: {}", image1.iter().filter(|&&v| v).count());
 
-    let image2 = image_enchance(image, &decoder, 50);
+    // Animation (if enabled) follows the longest enhancement, until quitting
+    let mut animation = Animation::from_args();
+    let image2 = image_enchance(image, &decoder, 50, |step, data| {
+        if let Some(a) = &mut animation {
+            let frame = image_frame(step, data);
+            if a.show(&frame).expect("Error while animating") == Control::Quit {
+                animation = None;
+            }
+        }
+    });
     println!("Problem #2: {}", image2.iter().filter(|&&v| v).count());
 }
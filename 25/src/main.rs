@@ -1,3 +1,5 @@
+use common::image::{Image, Rgb};
+use common::terminal::{grid_frame, Animation, Control};
 use grid::Grid;
 use itertools::Itertools;
 use std::{fs::File, io::BufRead, io::BufReader};
@@ -74,6 +76,22 @@ impl SeaFloor {
         result
     }
 
+    fn render(&self) -> Image {
+        Image::from_grid(&self.state, 4, |s| match s {
+            SeaCucumber::None => Rgb(8, 48, 107),
+            SeaCucumber::East => Rgb(230, 120, 40),
+            SeaCucumber::South => Rgb(120, 220, 160),
+        })
+    }
+
+    fn frame(&self) -> String {
+        grid_frame(&self.state, |s| match s {
+            SeaCucumber::None => ('.', Rgb(60, 90, 160)),
+            SeaCucumber::East => ('>', Rgb(230, 120, 40)),
+            SeaCucumber::South => ('v', Rgb(120, 220, 160)),
+        })
+    }
+
     fn parse(filepath: &str) -> Self {
         let file = File::open(filepath).expect("Error while opening cave file");
         let reader = BufReader::new(file);
@@ -82,7 +100,6 @@ impl SeaFloor {
 
         reader
             .lines()
-            .into_iter()
             .map(|l| l.unwrap())
             .for_each(|l| {
                 state.push_row(
@@ -110,9 +127,26 @@ fn main() {
 
     let mut sea = SeaFloor::parse(&filepath);
     let mut counter = 0usize;
-    while sea.step() {
+    let mut animation = Animation::from_args();
+    loop {
+        if let Some(a) = &mut animation {
+            let frame = format!("Step {}\n{}", counter, sea.frame());
+            if a.show(&frame).expect("Error while animating") == Control::Quit {
+                animation = None;
+            }
+        }
+
+        if !sea.step() {
+            break;
+        }
         counter += 1;
     }
 
     println!("Problem #1: {}", counter + 1);
+
+    if let Some(output) = common::args::option("--ppm") {
+        sea.render()
+            .save_ppm(&output)
+            .expect("Error while writing image");
+    }
 }
@@ -0,0 +1,9 @@
+// Value that follows `name` in the command line arguments
+// e.g. `--ppm out.ppm` -> option("--ppm") == Some("out.ppm")
+pub fn option(name: &str) -> Option<String> {
+    std::env::args().skip_while(|a| a != name).nth(1)
+}
+
+pub fn flag(name: &str) -> bool {
+    std::env::args().any(|a| a == name)
+}
@@ -0,0 +1,147 @@
+mod ramp;
+
+pub use ramp::{label_colour, ColourRamp};
+
+use grid::Grid;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Self = Self(0, 0, 0);
+    pub const WHITE: Self = Self(255, 255, 255);
+
+    // Linear interpolation between two colours (t=0 -> self, t=1 -> other)
+    pub fn blend(self, other: Self, t: f64) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mix =
+            |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+
+        Self(
+            mix(self.0, other.0),
+            mix(self.1, other.1),
+            mix(self.2, other.2),
+        )
+    }
+}
+
+// RGB raster where every cell of a grid is drawn as a square of `scale` pixels
+#[derive(Debug, Clone)]
+pub struct Image {
+    pixels: Grid<Rgb>,
+    scale: usize,
+}
+
+impl Image {
+    pub fn new(rows: usize, cols: usize, scale: usize) -> Self {
+        debug_assert!(scale > 0);
+
+        Self {
+            pixels: Grid::init(rows, cols, Rgb::BLACK),
+            scale,
+        }
+    }
+
+    pub fn from_grid<T, F>(grid: &Grid<T>, scale: usize, colour: F) -> Self
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        let mut result = Self::new(grid.rows(), grid.cols(), scale);
+        for r in 0..grid.rows() {
+            for c in 0..grid.cols() {
+                result.pixels[r][c] = colour(&grid[r][c]);
+            }
+        }
+
+        result
+    }
+
+    pub fn rows(&self) -> usize {
+        self.pixels.rows()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.pixels.cols()
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Rgb {
+        self.pixels[row][col]
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, colour: Rgb) {
+        self.pixels[row][col] = colour;
+    }
+
+    // Mixes `colour` on top of a cell, with `alpha` being its opacity
+    pub fn overlay(&mut self, row: usize, col: usize, colour: Rgb, alpha: f64) {
+        self.pixels[row][col] = self.pixels[row][col].blend(colour, alpha);
+    }
+
+    // Applies `overlay` to every cell for which `colour` outputs something
+    pub fn overlay_grid<T, F>(&mut self, grid: &Grid<T>, alpha: f64, colour: F)
+    where
+        F: Fn(&T) -> Option<Rgb>,
+    {
+        debug_assert!(grid.size() == self.pixels.size());
+
+        for r in 0..grid.rows() {
+            for c in 0..grid.cols() {
+                if let Some(colour) = colour(&grid[r][c]) {
+                    self.overlay(r, c, colour, alpha);
+                }
+            }
+        }
+    }
+
+    // Binary PPM (P6): a small text header followed by the raw RGB triplets
+    pub fn write_ppm<W: Write>(&self, output: &mut W) -> std::io::Result<()> {
+        let width = self.cols() * self.scale;
+        let height = self.rows() * self.scale;
+        write!(output, "P6\n{} {}\n255\n", width, height)?;
+
+        let mut line = Vec::with_capacity(width * 3);
+        for r in 0..self.rows() {
+            line.clear();
+            for pixel in self.pixels.iter_row(r) {
+                for _ in 0..self.scale {
+                    line.extend_from_slice(&[pixel.0, pixel.1, pixel.2]);
+                }
+            }
+
+            for _ in 0..self.scale {
+                output.write_all(&line)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn save_ppm(&self, filepath: &str) -> std::io::Result<()> {
+        let mut output = BufWriter::new(File::create(filepath)?);
+        self.write_ppm(&mut output)?;
+        output.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_ppm() {
+        let mut image = Image::new(1, 2, 2);
+        image.set(0, 1, Rgb::WHITE);
+
+        let mut output = Vec::new();
+        image.write_ppm(&mut output).unwrap();
+
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(&output[..header.len()], header);
+        assert_eq!(
+            &output[header.len()..],
+            [[0u8; 6], [255u8; 6], [0u8; 6], [255u8; 6]].concat()
+        );
+    }
+}
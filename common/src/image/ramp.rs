@@ -0,0 +1,94 @@
+use super::Rgb;
+
+// Piecewise linear colour gradient over [0, 1]
+#[derive(Debug, Clone)]
+pub struct ColourRamp {
+    stops: Vec<(f64, Rgb)>,
+}
+
+impl ColourRamp {
+    // Stops have to be sorted by position
+    pub fn new(stops: Vec<(f64, Rgb)>) -> Self {
+        debug_assert!(!stops.is_empty());
+        debug_assert!(stops.windows(2).all(|w| w[0].0 <= w[1].0));
+
+        Self { stops }
+    }
+
+    pub fn greyscale() -> Self {
+        Self::new(vec![(0.0, Rgb::BLACK), (1.0, Rgb::WHITE)])
+    }
+
+    // Deep water -> lowlands -> hills -> snow
+    pub fn terrain() -> Self {
+        Self::new(vec![
+            (0.0, Rgb(8, 48, 107)),
+            (0.3, Rgb(65, 171, 93)),
+            (0.6, Rgb(217, 196, 120)),
+            (0.85, Rgb(140, 100, 70)),
+            (1.0, Rgb::WHITE),
+        ])
+    }
+
+    // Black -> red -> yellow -> white
+    pub fn heat() -> Self {
+        Self::new(vec![
+            (0.0, Rgb::BLACK),
+            (0.4, Rgb(200, 30, 0)),
+            (0.8, Rgb(255, 220, 0)),
+            (1.0, Rgb::WHITE),
+        ])
+    }
+
+    pub fn at(&self, t: f64) -> Rgb {
+        let first = self.stops.first().unwrap();
+        let last = self.stops.last().unwrap();
+
+        if t <= first.0 {
+            return first.1;
+        }
+
+        self.stops
+            .windows(2)
+            .find(|w| t <= w[1].0)
+            .map(|w| {
+                let span = w[1].0 - w[0].0;
+                if span > 0.0 {
+                    w[0].1.blend(w[1].1, (t - w[0].0) / span)
+                } else {
+                    w[1].1
+                }
+            })
+            .unwrap_or(last.1)
+    }
+
+    // Maps `value` from [min, max] into the ramp
+    pub fn scaled(&self, value: f64, min: f64, max: f64) -> Rgb {
+        if max > min {
+            self.at((value - min) / (max - min))
+        } else {
+            self.at(0.0)
+        }
+    }
+}
+
+// Distinct colour for each label, walking the hue circle by the golden angle
+// so that consecutive labels are far apart
+pub fn label_colour(label: usize) -> Rgb {
+    const GOLDEN_ANGLE: f64 = 137.507_764;
+
+    let hue = (label as f64 * GOLDEN_ANGLE) % 360.0 / 60.0;
+    let x = 1.0 - (hue % 2.0 - 1.0).abs();
+    let (r, g, b) = match hue as usize {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+
+    // Slightly desaturated, so overlays do not hide what is below them
+    let channel = |v: f64| (55.0 + v * 200.0).round() as u8;
+    Rgb(channel(r), channel(g), channel(b))
+}
@@ -0,0 +1,37 @@
+use std::fmt::Write;
+
+// Quoted JSON string, with quotes, backslashes and control characters escaped
+pub fn string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                write!(result, "\\u{:04x}", c as u32).unwrap()
+            }
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string() {
+        assert_eq!(string(""), "\"\"");
+        assert_eq!(string("a\"b\\c"), "\"a\\\"b\\\\c\"");
+        assert_eq!(string("\r\n\t\x1b[0m"), "\"\\r\\n\\t\\u001b[0m\"");
+        assert_eq!(string("é→"), "\"é→\"");
+    }
+}
@@ -0,0 +1,4 @@
+pub mod args;
+pub mod image;
+pub mod json;
+pub mod terminal;
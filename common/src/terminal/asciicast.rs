@@ -0,0 +1,102 @@
+use crate::json;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::Instant;
+
+// Writes terminal output in the asciicast v2 format: a JSON header followed by
+// one JSON array per chunk of output, `[seconds, "o", data]`
+pub struct Recorder<W: Write> {
+    output: W,
+    start: Option<Instant>,
+}
+
+impl Recorder<BufWriter<File>> {
+    pub fn create(filepath: &str) -> std::io::Result<Self> {
+        Ok(Recorder::new(BufWriter::new(File::create(filepath)?)))
+    }
+}
+
+impl<W: Write> Recorder<W> {
+    pub fn new(output: W) -> Self {
+        Self {
+            output,
+            start: None,
+        }
+    }
+
+    // The terminal size is taken from the first recorded frame
+    pub fn record(&mut self, frame: &str) -> std::io::Result<()> {
+        let start = match self.start {
+            Some(start) => start,
+            None => {
+                let (width, height) = size(frame);
+                writeln!(
+                    self.output,
+                    "{{\"version\": 2, \"width\": {}, \"height\": {}}}",
+                    width, height
+                )?;
+                *self.start.insert(Instant::now())
+            }
+        };
+
+        writeln!(
+            self.output,
+            "[{:.6}, \"o\", {}]",
+            start.elapsed().as_secs_f64(),
+            json::string(&frame.replace('\n', "\r\n"))
+        )?;
+        self.output.flush()
+    }
+}
+
+// Number of visible columns and lines of a frame, skipping ANSI sequences
+fn size(frame: &str) -> (usize, usize) {
+    let width = frame
+        .lines()
+        .map(|line| {
+            let mut escape = false;
+            line.chars()
+                .filter(|&c| {
+                    if c == '\x1b' {
+                        escape = true;
+                    } else if escape && c.is_ascii_alphabetic() {
+                        escape = false;
+                        return false;
+                    }
+                    !escape
+                })
+                .count()
+        })
+        .max()
+        .unwrap_or(0);
+
+    (width.max(1), frame.lines().count().max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size() {
+        assert_eq!(size(""), (1, 1));
+        assert_eq!(size("ab\nabcd\n"), (4, 2));
+        assert_eq!(size("\x1b[2J\x1b[H\x1b[38;2;1;2;3mabc\x1b[0m\n"), (3, 1));
+    }
+
+    #[test]
+    fn test_record() {
+        let mut output = Vec::new();
+        let mut recorder = Recorder::new(&mut output);
+        recorder.record("ab\n\x1b[0mc\n").unwrap();
+        recorder.record("\"d\"\n").unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "{\"version\": 2, \"width\": 2, \"height\": 2}");
+        assert!(lines[1].starts_with('['));
+        assert!(lines[1].ends_with(", \"o\", \"ab\\r\\n\\u001b[0mc\\r\\n\"]"));
+        assert!(lines[2].ends_with(", \"o\", \"\\\"d\\\"\\r\\n\"]"));
+    }
+}
@@ -0,0 +1,235 @@
+mod asciicast;
+
+use crate::image::Rgb;
+use asciicast::Recorder;
+use grid::Grid;
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Continue,
+    Quit,
+}
+
+// Shows a sequence of frames on the terminal, one after the other, at a fixed
+// pace. Optionally:
+// * reads commands from stdin, one per line: 'p' toggles play/pause, 'q'
+//   quits and an empty line shows the next frame while paused
+// * records every frame into an asciicast file
+pub struct Animation {
+    delay: Duration,
+    controls: Option<Receiver<String>>,
+    paused: bool,
+    recorder: Option<Recorder<BufWriter<File>>>,
+    frames: usize,
+}
+
+impl Animation {
+    const CLEAR: &'static str = "\x1b[2J";
+    const HOME: &'static str = "\x1b[H";
+    const HELP: &'static str = "[enter] step  [p] play/pause  [q] quit";
+
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            controls: None,
+            paused: false,
+            recorder: None,
+            frames: 0,
+        }
+    }
+
+    // Built from the command line arguments: `--animate` enables it, with
+    // `--delay <ms>`, `--controls` and `--record <file>` as options. Recording
+    // implies `--animate`
+    pub fn from_args() -> Option<Self> {
+        let record = crate::args::option("--record");
+        if !crate::args::flag("--animate") && record.is_none() {
+            return None;
+        }
+
+        let delay = crate::args::option("--delay")
+            .map(|d| d.parse().expect("Invalid animation delay"))
+            .unwrap_or(100);
+        let mut result = Self::new(Duration::from_millis(delay));
+
+        if crate::args::flag("--controls") {
+            result = result.with_controls();
+        }
+        if let Some(filepath) = record {
+            result = result
+                .with_recording(&filepath)
+                .expect("Error while creating recording");
+        }
+
+        Some(result)
+    }
+
+    pub fn with_controls(mut self) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line.trim().to_owned()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        self.controls = Some(receiver);
+        self
+    }
+
+    pub fn with_recording(mut self, filepath: &str) -> std::io::Result<Self> {
+        self.recorder = Some(Recorder::create(filepath)?);
+        Ok(self)
+    }
+
+    // Draws a frame and waits for the next one to be due
+    pub fn show(&mut self, frame: &str) -> std::io::Result<Control> {
+        let mut output = String::with_capacity(frame.len() + 16);
+        if self.frames == 0 {
+            output.push_str(Self::CLEAR);
+        }
+        output.push_str(Self::HOME);
+        output.push_str(frame);
+        if self.controls.is_some() {
+            output.push('\n');
+            output.push_str(Self::HELP);
+        }
+        output.push('\n');
+        self.frames += 1;
+
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(output.as_bytes())?;
+        stdout.flush()?;
+
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record(&output)?;
+        }
+
+        Ok(self.wait())
+    }
+
+    fn wait(&mut self) -> Control {
+        let deadline = Instant::now() + self.delay;
+
+        while let Some(controls) = &self.controls {
+            let command = if self.paused {
+                controls.recv().map_err(|_| RecvTimeoutError::Disconnected)
+            } else {
+                controls.recv_timeout(
+                    deadline.saturating_duration_since(Instant::now()),
+                )
+            };
+
+            match command.as_deref() {
+                Ok("q") => return Control::Quit,
+                Ok("p") => {
+                    self.paused = !self.paused;
+                    if !self.paused {
+                        return Control::Continue;
+                    }
+                }
+                Ok(_) if self.paused => return Control::Continue,
+                Ok(_) => (),
+                Err(RecvTimeoutError::Timeout) => return Control::Continue,
+                // Stdin was closed, so keep playing without controls
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.controls = None;
+                    self.paused = false;
+                }
+            }
+        }
+
+        std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+        Control::Continue
+    }
+}
+
+// Draws every cell of a grid as a coloured character
+pub fn grid_frame<T, F>(grid: &Grid<T>, cell: F) -> String
+where
+    F: Fn(&T) -> (char, Rgb),
+{
+    let mut result =
+        String::with_capacity(grid.rows() * (grid.cols() * 20 + 5));
+
+    for r in 0..grid.rows() {
+        let mut current = None;
+        for value in grid.iter_row(r) {
+            let (c, colour) = cell(value);
+
+            // Colour codes are only emitted when the colour changes
+            if current != Some(colour) {
+                result.push_str(&format!(
+                    "\x1b[38;2;{};{};{}m",
+                    colour.0, colour.1, colour.2
+                ));
+                current = Some(colour);
+            }
+            result.push(c);
+        }
+        result.push_str("\x1b[0m\n");
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_frame() {
+        let grid = Grid::from_vec(vec![1, 1, 2, 1], 2);
+        let frame = grid_frame(&grid, |v| match v {
+            1 => ('a', Rgb(1, 2, 3)),
+            _ => ('b', Rgb::WHITE),
+        });
+
+        assert_eq!(
+            frame,
+            "\x1b[38;2;1;2;3maa\x1b[0m\n\
+             \x1b[38;2;255;255;255mb\x1b[38;2;1;2;3ma\x1b[0m\n"
+        );
+    }
+
+    fn controlled(delay: u64) -> (Animation, mpsc::Sender<String>) {
+        let (sender, receiver) = mpsc::channel();
+        let mut animation = Animation::new(Duration::from_millis(delay));
+        animation.controls = Some(receiver);
+        (animation, sender)
+    }
+
+    #[test]
+    fn test_wait() {
+        // Frames are shown at the given pace
+        let (mut animation, _sender) = controlled(20);
+        let start = Instant::now();
+        assert_eq!(animation.wait(), Control::Continue);
+        assert!(start.elapsed() >= Duration::from_millis(20));
+
+        let (mut animation, sender) = controlled(10_000);
+        sender.send("q".to_owned()).unwrap();
+        assert_eq!(animation.wait(), Control::Quit);
+
+        // Paused until the next frame is asked for, without a timeout
+        for command in ["p", "x", "p"] {
+            sender.send(command.to_owned()).unwrap();
+        }
+        assert_eq!(animation.wait(), Control::Continue);
+        assert!(animation.paused);
+        assert_eq!(animation.wait(), Control::Continue);
+        assert!(!animation.paused);
+
+        // Closing stdin falls back to playing without controls
+        drop(sender);
+        animation.delay = Duration::ZERO;
+        assert_eq!(animation.wait(), Control::Continue);
+        assert!(animation.controls.is_none());
+    }
+}